@@ -0,0 +1,70 @@
+use super::{exit_on_error, Target};
+use huawei_solar_rs::{registers, BatteryLimits, BatteryWorkingModeSetting, ForcibleCommand, HuaweiSolar, RegisterTransport};
+
+pub const WORKING_MODES: [(&str, BatteryWorkingModeSetting); 6] = [
+    ("adaptive", BatteryWorkingModeSetting::Adaptive),
+    ("fixed_charge_discharge", BatteryWorkingModeSetting::FixedChargeDischarge),
+    ("self_consumption", BatteryWorkingModeSetting::MaximiseSelfConsumption),
+    ("time_of_use_lg", BatteryWorkingModeSetting::TimeOfUseLg),
+    ("fully_fed_to_grid", BatteryWorkingModeSetting::FullyFedToGrid),
+    ("time_of_use", BatteryWorkingModeSetting::TimeOfUseLuna2000)
+];
+
+pub fn parse_working_mode(value: &str) -> Option<BatteryWorkingModeSetting> {
+    WORKING_MODES.iter().find(|(name, _)| *name == value).map(|(_, mode)| *mode)
+}
+
+// Limits left as `None` keep their current value.
+pub struct LimitChanges {
+    pub max_charge_power: Option<f64>,
+    pub max_discharge_power: Option<f64>,
+    pub charge_cutoff_soc: Option<f64>,
+    pub discharge_cutoff_soc: Option<f64>
+}
+
+pub fn show(target: &Target) {
+    with_inverter!(target, inverter => print_battery(&mut inverter));
+}
+
+pub fn set_working_mode(target: &Target, mode: BatteryWorkingModeSetting) {
+    with_inverter!(target, inverter => {
+        exit_on_error(inverter.set_battery_working_mode(mode), "setting working mode");
+        println!("Set working mode to {}", mode);
+        print_battery(&mut inverter);
+    });
+}
+
+pub fn set_limits(target: &Target, changes: LimitChanges) {
+    with_inverter!(target, inverter => {
+        let current = exit_on_error(inverter.read_battery_limits(), "reading battery limits");
+        let limits = BatteryLimits {
+            max_charge_power: changes.max_charge_power.unwrap_or(current.max_charge_power),
+            max_discharge_power: changes.max_discharge_power.unwrap_or(current.max_discharge_power),
+            charge_cutoff_soc: changes.charge_cutoff_soc.unwrap_or(current.charge_cutoff_soc),
+            discharge_cutoff_soc: changes.discharge_cutoff_soc.unwrap_or(current.discharge_cutoff_soc)
+        };
+        exit_on_error(inverter.set_battery_limits(&limits), "setting battery limits");
+        println!("Updated battery limits");
+        print_battery(&mut inverter);
+    });
+}
+
+pub fn force(target: &Target, command: ForcibleCommand) {
+    with_inverter!(target, inverter => {
+        exit_on_error(inverter.force_battery(command), "sending forcible charge/discharge command");
+        println!("Sent forcible command: {}", command);
+        print_battery(&mut inverter);
+    });
+}
+
+fn print_battery<C: RegisterTransport>(inverter: &mut HuaweiSolar<C>) {
+    let mode = exit_on_error(inverter.read_battery_working_mode_setting(), "reading working mode");
+    let limits = exit_on_error(inverter.read_battery_limits(), "reading battery limits");
+    println!("Working mode: {}", mode);
+    println!("Max charge power: {} W, max discharge power: {} W", limits.max_charge_power, limits.max_discharge_power);
+    println!("End of charge: {} %, end of discharge: {} %", limits.charge_cutoff_soc, limits.discharge_cutoff_soc);
+    match (inverter.read_numeric_register(&registers::STORAGE_STATE_OF_CAPACITY), inverter.read_numeric_register(&registers::STORAGE_CHARGE_DISCHARGE_POWER)) {
+        (Ok(soc), Ok(power)) => println!("State of charge: {} %, charge/discharge power: {} W", soc, power),
+        (Err(e), _) | (_, Err(e)) => println!("State of charge: not available ({})", e)
+    }
+}
@@ -0,0 +1,38 @@
+use super::{exit_on_error, Target};
+use chrono::prelude::*;
+use huawei_solar_rs::{registers, HuaweiSolar, RegisterTransport};
+
+pub enum TimeZoneSource {
+    Inverter,
+    Host,
+    Minutes(i16)
+}
+
+pub fn show(target: &Target) {
+    with_inverter!(target, inverter => print_times(&mut inverter));
+}
+
+pub fn sync(target: &Target, time_zone: TimeZoneSource) {
+    with_inverter!(target, inverter => {
+        let offset = match time_zone {
+            TimeZoneSource::Inverter => {
+                let minutes = exit_on_error(inverter.read_numeric_register(&registers::TIME_ZONE), "reading time zone");
+                FixedOffset::east_opt(minutes as i32 * 60)
+            },
+            TimeZoneSource::Host => Some(*Local::now().offset()),
+            TimeZoneSource::Minutes(minutes) => FixedOffset::east_opt(minutes as i32 * 60)
+        }.expect("Bad time zone!");
+        let now = Utc::now().with_timezone(&offset);
+        exit_on_error(inverter.set_system_time(&now), "setting system time");
+        println!("Set inverter clock to {}", now.format("%Y-%m-%d %H:%M:%S %:z"));
+        print_times(&mut inverter);
+    });
+}
+
+fn print_times<C: RegisterTransport>(inverter: &mut HuaweiSolar<C>) {
+    let time = exit_on_error(inverter.read_system_time(), "reading system time");
+    let host = Utc::now();
+    println!("Inverter time: {}", time.format("%Y-%m-%d %H:%M:%S %:z"));
+    println!("Host time: {}", host.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %:z"));
+    println!("Drift: {} s", (time.with_timezone(&Utc) - host).num_seconds());
+}
@@ -0,0 +1,68 @@
+use super::{exit_on_error, Target};
+use huawei_solar_rs::{ExportLimit, HuaweiSolar, PowerLimit, RegisterTransport};
+
+pub enum ActiveLimitSetting {
+    Limit(PowerLimit),
+    None
+}
+
+// Accepts "50%", "3000W" (or plain "3000") and "none".
+pub fn parse_active_limit(value: &str) -> Option<ActiveLimitSetting> {
+    if value == "none" {
+        return Some(ActiveLimitSetting::None);
+    }
+    if let Some(percent) = value.strip_suffix('%') {
+        return percent.trim().parse().ok().map(|percent| ActiveLimitSetting::Limit(PowerLimit::Percent(percent)));
+    }
+    value.strip_suffix('W').unwrap_or(value).trim().parse().ok().map(|watts| ActiveLimitSetting::Limit(PowerLimit::Watts(watts)))
+}
+
+// Accepts "unlimited", "zero", "50%" and "3000W" (or plain "3000").
+pub fn parse_export_limit(value: &str) -> Option<ExportLimit> {
+    match value {
+        "unlimited" => Some(ExportLimit::Unlimited),
+        "zero" => Some(ExportLimit::Zero),
+        _ => match value.strip_suffix('%') {
+            Some(percent) => percent.trim().parse().ok().map(ExportLimit::Percent),
+            None => value.strip_suffix('W').unwrap_or(value).trim().parse().ok().map(ExportLimit::Watts)
+        }
+    }
+}
+
+pub fn show(target: &Target) {
+    with_inverter!(target, inverter => print_limits(&mut inverter));
+}
+
+pub fn set_active(target: &Target, setting: ActiveLimitSetting) {
+    with_inverter!(target, inverter => {
+        match setting {
+            ActiveLimitSetting::Limit(limit) => {
+                exit_on_error(inverter.set_active_power_limit(limit), "setting active power limit");
+                println!("Limited active power to {}", limit);
+            },
+            ActiveLimitSetting::None => {
+                exit_on_error(inverter.clear_active_power_limit(), "clearing active power limit");
+                println!("Removed active power limit");
+            }
+        }
+        print_limits(&mut inverter);
+    });
+}
+
+pub fn set_export(target: &Target, limit: ExportLimit) {
+    with_inverter!(target, inverter => {
+        exit_on_error(inverter.set_export_limit(limit), "setting export limit");
+        println!("Set grid export limit to {}", limit);
+        print_limits(&mut inverter);
+    });
+}
+
+fn print_limits<C: RegisterTransport>(inverter: &mut HuaweiSolar<C>) {
+    let active = exit_on_error(inverter.read_active_power_limit(), "reading active power limit");
+    println!("Active power derating: {} % / {} W", active.percent, active.watts);
+    println!("Effective active power limit: {} W", active.effective_watts);
+    match inverter.read_export_limit() {
+        Ok(export) => println!("Grid export limit: {}", export),
+        Err(e) => println!("Grid export limit: not available ({})", e)
+    }
+}
@@ -1,4 +1,4 @@
-use huawei_solar_rs::HuaweiSolar;
+use huawei_solar_rs::{Error, HuaweiSolar, RegisterTransport};
 
 use std::fs::File;
 use std::io::Write;
@@ -8,14 +8,61 @@ pub enum OutputFormat {
     PrettyPrint
 }
 
+pub enum Protocol {
+    Tcp,
+    RtuOverTcp
+}
+
+pub struct Target {
+    pub protocol: Protocol,
+    pub ip: String,
+    pub port: u16,
+    pub slave_id: u8
+}
+
+// Connects to `target` and evaluates `body` with `inverter` bound to the connection, whose
+// type depends on the protocol.
+macro_rules! with_inverter {
+    ($target:expr, $inverter:ident => $body:expr) => {
+        match $target.protocol {
+            $crate::logic::Protocol::Tcp => {
+                let mut $inverter = $crate::logic::connect(huawei_solar_rs::HuaweiSolar::new_connection(&$target.ip, $target.port, $target.slave_id));
+                $body
+            },
+            $crate::logic::Protocol::RtuOverTcp => {
+                let mut $inverter = $crate::logic::connect(huawei_solar_rs::HuaweiSolar::new_rtu_over_tcp_connection(&$target.ip, $target.port, $target.slave_id));
+                $body
+            }
+        }
+    };
+}
+
+pub mod battery;
+pub mod clock;
+pub mod limit;
 mod params_parse;
+pub mod power;
+pub mod reactive;
 mod pretty_print;
+pub mod simulate;
+
+fn connect<C: RegisterTransport>(connection: Result<HuaweiSolar<C>, Error>) -> HuaweiSolar<C> {
+    exit_on_error(connection, "connecting to inverter")
+}
+
+// Prints the error and exits, for commands that cannot go on after a failed request.
+fn exit_on_error<T>(result: Result<T, Error>, action: &str) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            println!("Error {}: {}", action, e);
+            std::process::exit(1);
+        }
+    }
+}
 
-pub fn query(ip: &str, port: u16, slave_id: u8, query_params: &str, output_format: OutputFormat, 
-    output_path: &str) 
-{
-    let mut inverter = HuaweiSolar::new_connection(ip, port, slave_id).unwrap();
-    let map = params_parse::parse_to_map(&mut inverter, query_params);
+pub fn query(target: &Target, query_params: &str, output_format: OutputFormat, output_path: &str) {
+    let map = with_inverter!(target, inverter => params_parse::parse_to_map(&mut inverter, query_params));
 
     let output: String = match output_format {
         OutputFormat::Json => {
@@ -26,7 +73,7 @@ pub fn query(ip: &str, port: u16, slave_id: u8, query_params: &str, output_forma
         }
     };
     if output_path == "-" {
-        println!("");
+        println!();
         println!("{}", output);
     } else {
         println!("Writing to {}", output_path);
@@ -1,200 +1,221 @@
-use huawei_solar_rs::HuaweiSolar;
-use huawei_solar_rs::NumericRegisterTrait;
-use huawei_solar_rs::StringRegister;
+use huawei_solar_rs::{describe_fault_code, Alarm, BatteryRunningStatus, BatteryStatus, BatteryWorkingMode, DeviceInfo, DeviceStatus, Error, FaultCode, HuaweiSolar, PowerMeter, PvString};
+use huawei_solar_rs::{Register, RegisterTransport, RegisterValue};
 use std::collections::BTreeMap;
-use serde::{Serialize, Deserialize};
+use serde::Serialize;
 use huawei_solar_rs::registers;
 use std::fmt;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Debug)]
 #[serde(untagged)]
 pub enum Variant {
     String(String),
-    Float(f64)
-}
-
-fn unpack_numeric(v: &Variant) -> f64 {
-    match v {
-        Variant::Float(v) => *v,
-        _ => 0.0
-    }
+    Float(f64),
+    PvStrings(Vec<PvString>),
+    Alarms(Vec<Alarm>),
+    FaultCode(FaultCode),
+    Battery(BatteryStatus),
+    PowerMeter(PowerMeter),
+    DeviceInfo(DeviceInfo)
 }
 
 impl fmt::Display for Variant {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Variant::String(s) => write!(f, "{}", s),
-            Variant::Float(flt) => write!(f, "{}", flt)
+            Variant::Float(flt) => write!(f, "{}", flt),
+            Variant::PvStrings(strings) => {
+                let strings: Vec<String> = strings.iter().map(|s| format!("PV{}: {} V, {} A", s.index, or_unavailable(s.voltage), or_unavailable(s.current))).collect();
+                write!(f, "{}", strings.join("; "))
+            },
+            Variant::Alarms(alarms) => {
+                let alarms: Vec<String> = alarms.iter().map(|alarm| alarm.to_string()).collect();
+                write!(f, "{}", alarms.join("; "))
+            },
+            Variant::FaultCode(fault) => write!(f, "{} ({})", fault.description, fault.code),
+            Variant::Battery(battery) => write!(f, "{} %, {} W, {}", battery.state_of_charge, battery.charge_discharge_power, battery.running_status),
+            Variant::PowerMeter(meter) => write!(f, "{} W, {} var", meter.active_power, meter.reactive_power),
+            Variant::DeviceInfo(info) => write!(f, "{} ({})", info.model_name, info.serial_number)
         }
     }
 }
 
-fn handle_string_reg(inv: &mut HuaweiSolar, name: &str, reg: &StringRegister, map: &mut BTreeMap<String, Variant>) {
-    let value = match inv.read_string_register(reg) {
-        Ok(value) => value,
-        Err(e) => {
-            println!("Error reading {}: {}", name, e);
-            return;
-        }
-    };
-    map.insert(name.to_string(), Variant::String(value));
+pub fn or_unavailable(value: Option<f64>) -> String {
+    value.map_or_else(|| "n/a".to_string(), |value| value.to_string())
 }
 
-fn handle_numeric_reg<T: NumericRegisterTrait>(inv: &mut HuaweiSolar, name: &str, reg: &T, map: &mut BTreeMap<String, Variant>) {
-    let value = match inv.read_numeric_register(reg) {
-        Ok(value) => value,
-        Err(e) => {
-            println!("Error reading {}: {}", name, e);
-            return;
-        }
-    };
-    map.insert(name.to_string(), Variant::Float(value));
+enum Param {
+    Plain(&'static dyn Register),
+    DeviceStatus(&'static dyn Register),
+    FaultCode(&'static dyn Register),
+    BatteryRunningStatus(&'static dyn Register),
+    BatteryWorkingMode(&'static dyn Register),
+    Timestamp(&'static dyn Register)
 }
 
-fn handle_device_status(inv: &mut HuaweiSolar, map: &mut BTreeMap<String, Variant>) {
-    let value = match inv.read_device_status(&registers::DEVICE_STATUS) {
-        Ok(value) => value,
-        Err(e) => {
-            println!("Error reading device status: {}", e);
-            return;
+impl Param {
+    fn register(&self) -> &'static dyn Register {
+        match self {
+            Param::Plain(reg) | Param::DeviceStatus(reg) | Param::FaultCode(reg) | Param::BatteryRunningStatus(reg)
+                | Param::BatteryWorkingMode(reg) | Param::Timestamp(reg) => *reg
         }
-    };
-    map.insert("device_status".to_string(), Variant::String(value));
+    }
 }
 
-fn handle_timestamp_reg<T: NumericRegisterTrait>(inv: &mut HuaweiSolar, name: &str, reg: &T, map: &mut BTreeMap<String, Variant>) {
-    if !map.contains_key("time_zone") {
-        handle_numeric_reg(inv, "time_zone", &registers::TIME_ZONE, map);
+const PARAMS: [(&str, Param); 30] = [
+    ("model_name", Param::Plain(&registers::MODEL_NAME)),
+    ("serial_number", Param::Plain(&registers::SERIAL_NUMBER)),
+    ("input_power", Param::Plain(&registers::INPUT_POWER)),
+    ("grid_voltage", Param::Plain(&registers::GRID_VOLTAGE)),
+    ("line_voltage_a_b", Param::Plain(&registers::LINE_VOLTAGE_A_B)),
+    ("line_voltage_b_c", Param::Plain(&registers::LINE_VOLTAGE_B_C)),
+    ("line_voltage_c_a", Param::Plain(&registers::LINE_VOLTAGE_C_A)),
+    ("phase_a_voltage", Param::Plain(&registers::PHASE_A_VOLTAGE)),
+    ("phase_b_voltage", Param::Plain(&registers::PHASE_B_VOLTAGE)),
+    ("phase_c_voltage", Param::Plain(&registers::PHASE_C_VOLTAGE)),
+    ("grid_current", Param::Plain(&registers::GRID_CURRENT)),
+    ("phase_a_current", Param::Plain(&registers::PHASE_A_CURRENT)),
+    ("phase_b_current", Param::Plain(&registers::PHASE_B_CURRENT)),
+    ("phase_c_current", Param::Plain(&registers::PHASE_C_CURRENT)),
+    ("day_active_power_peak", Param::Plain(&registers::DAY_ACTIVE_POWER_PEAK)),
+    ("active_power", Param::Plain(&registers::ACTIVE_POWER)),
+    ("reactive_power", Param::Plain(&registers::REACTIVE_POWER)),
+    ("power_factor", Param::Plain(&registers::POWER_FACTOR)),
+    ("grid_frequency", Param::Plain(&registers::GRID_FREQUENCY)),
+    ("efficiency", Param::Plain(&registers::EFFICIENCY)),
+    ("internal_temperature", Param::Plain(&registers::INTERNAL_TEMPERATURE)),
+    ("insulation_resistance", Param::Plain(&registers::INSULATION_RESISTANCE)),
+    ("device_status", Param::DeviceStatus(&registers::DEVICE_STATUS)),
+    ("fault_code", Param::FaultCode(&registers::FAULT_CODE)),
+    ("startup_time", Param::Timestamp(&registers::STARTUP_TIME)),
+    ("shutdown_time", Param::Timestamp(&registers::SHUTDOWN_TIME)),
+    ("accumulated_yield_energy", Param::Plain(&registers::ACCUMULATED_YIELD_ENERGY)),
+    ("daily_yield_energy", Param::Plain(&registers::DAILY_YIELD_ENERGY)),
+    ("optimizers_total", Param::Plain(&registers::NUMBER_OF_OPTIMIZERS)),
+    ("optimizers_online", Param::Plain(&registers::NUMBER_OF_ONLINE_OPTIMIZERS)),
+];
+
+// Registers of attached LUNA2000 batteries, not included in "all".
+const BATTERY_PARAMS: [(&str, Param); 13] = [
+    ("battery_state_of_charge", Param::Plain(&registers::STORAGE_STATE_OF_CAPACITY)),
+    ("battery_charge_discharge_power", Param::Plain(&registers::STORAGE_CHARGE_DISCHARGE_POWER)),
+    ("battery_bus_voltage", Param::Plain(&registers::STORAGE_BUS_VOLTAGE)),
+    ("battery_bus_current", Param::Plain(&registers::STORAGE_BUS_CURRENT)),
+    ("battery_running_status", Param::BatteryRunningStatus(&registers::STORAGE_RUNNING_STATUS)),
+    ("battery_working_mode", Param::BatteryWorkingMode(&registers::STORAGE_UNIT_1_WORKING_MODE)),
+    ("battery_rated_capacity", Param::Plain(&registers::STORAGE_RATED_CAPACITY)),
+    ("battery_day_charge_energy", Param::Plain(&registers::STORAGE_DAY_CHARGE_ENERGY)),
+    ("battery_day_discharge_energy", Param::Plain(&registers::STORAGE_DAY_DISCHARGE_ENERGY)),
+    ("battery_total_charge_energy", Param::Plain(&registers::STORAGE_TOTAL_CHARGE_ENERGY)),
+    ("battery_total_discharge_energy", Param::Plain(&registers::STORAGE_TOTAL_DISCHARGE_ENERGY)),
+    ("battery_unit_1_temperature", Param::Plain(&registers::STORAGE_UNIT_1_TEMPERATURE)),
+    ("battery_unit_2_temperature", Param::Plain(&registers::STORAGE_UNIT_2_TEMPERATURE)),
+];
+
+// Params made of several registers, read with dedicated `HuaweiSolar` methods.
+const COMPOSITE_PARAMS: [&str; 5] = ["pv_strings", "alarms", "battery", "power_meter", "device_info"];
+// "all" only covers the inverter itself. Batteries and power meters are opt-in, as reading them
+// from an inverter without one attached just produces errors.
+const ALL_COMPOSITE_PARAMS: [&str; 3] = ["pv_strings", "alarms", "device_info"];
+
+fn read_composite<C: RegisterTransport>(inverter: &mut HuaweiSolar<C>, name: &str) -> Result<Variant, Error> {
+    match name {
+        "pv_strings" => Ok(Variant::PvStrings(inverter.read_pv_strings()?)),
+        "alarms" => Ok(Variant::Alarms(inverter.read_alarms()?)),
+        "battery" => Ok(Variant::Battery(inverter.read_battery()?)),
+        "power_meter" => Ok(Variant::PowerMeter(inverter.read_power_meter()?)),
+        "device_info" => Ok(Variant::DeviceInfo(inverter.read_device_info()?)),
+        _ => unreachable!()
     }
-    let value = match inv.read_numeric_register(reg) {
-        Ok(value) => value,
-        Err(e) => {
-            println!("Error reading {}: {}", name, e);
-            return;
-        }
-    };
-    map.insert(name.to_string(), Variant::Float(value - 60.0 * unpack_numeric(map.get("time_zone").unwrap())));
 }
 
-pub fn parse_to_map(inverter: &mut HuaweiSolar, query_params: &str) -> BTreeMap<String, Variant> {
+fn find_param(name: &str) -> Option<&'static (&'static str, Param)> {
+    PARAMS.iter().chain(BATTERY_PARAMS.iter()).find(|(param_name, _)| *param_name == name)
+}
+
+pub fn find_register(name: &str) -> Option<&'static dyn Register> {
+    find_param(name).map(|(_, param)| param.register())
+}
+
+pub fn parse_to_map<C: RegisterTransport>(inverter: &mut HuaweiSolar<C>, query_params: &str) -> BTreeMap<String, Variant> {
     let mut map = BTreeMap::new();
-    if query_params.contains("all") {
-        handle_string_reg(inverter, "model_name", &registers::MODEL_NAME, &mut map);
-        handle_string_reg(inverter, "serial_number", &registers::SERIAL_NUMBER, &mut map);
-        handle_numeric_reg(inverter, "input_power", &registers::INPUT_POWER, &mut map);
-        handle_numeric_reg(inverter, "grid_voltage", &registers::GRID_VOLTAGE, &mut map);
-        handle_numeric_reg(inverter, "line_voltage_a_b", &registers::LINE_VOLTAGE_A_B, &mut map);
-        handle_numeric_reg(inverter, "line_voltage_b_c", &registers::LINE_VOLTAGE_B_C, &mut map);
-        handle_numeric_reg(inverter, "line_voltage_c_a", &registers::LINE_VOLTAGE_C_A, &mut map);
-        handle_numeric_reg(inverter, "phase_a_voltage", &registers::PHASE_A_VOLTAGE, &mut map);
-        handle_numeric_reg(inverter, "phase_b_voltage", &registers::PHASE_B_VOLTAGE, &mut map);
-        handle_numeric_reg(inverter, "phase_c_voltage", &registers::PHASE_C_VOLTAGE, &mut map);
-        handle_numeric_reg(inverter, "grid_current", &registers::GRID_CURRENT, &mut map);
-        handle_numeric_reg(inverter, "phase_a_current", &registers::PHASE_A_CURRENT, &mut map);
-        handle_numeric_reg(inverter, "phase_b_current", &registers::PHASE_B_CURRENT, &mut map);
-        handle_numeric_reg(inverter, "phase_c_current", &registers::PHASE_C_CURRENT, &mut map);
-        handle_numeric_reg(inverter, "day_active_power_peak", &registers::DAY_ACTIVE_POWER_PEAK, &mut map);
-        handle_numeric_reg(inverter, "active_power", &registers::ACTIVE_POWER, &mut map);
-        handle_numeric_reg(inverter, "reactive_power", &registers::REACTIVE_POWER, &mut map);
-        handle_numeric_reg(inverter, "power_factor", &registers::POWER_FACTOR, &mut map);
-        handle_numeric_reg(inverter, "grid_frequency", &registers::GRID_FREQUENCY, &mut map);
-        handle_numeric_reg(inverter, "efficiency", &registers::EFFICIENCY, &mut map);
-        handle_numeric_reg(inverter, "internal_temperature", &registers::INTERNAL_TEMPERATURE, &mut map);
-        handle_numeric_reg(inverter, "insulation_resistance", &registers::INSULATION_RESISTANCE, &mut map);
-        handle_device_status(inverter, &mut map);
-        handle_timestamp_reg(inverter, "startup_time", &registers::STARTUP_TIME, &mut map);
-        handle_timestamp_reg(inverter, "shutdown_time", &registers::SHUTDOWN_TIME, &mut map);
-        handle_numeric_reg(inverter, "accumulated_yield_energy", &registers::ACCUMULATED_YIELD_ENERGY, &mut map);
-        handle_numeric_reg(inverter, "daily_yield_energy", &registers::DAILY_YIELD_ENERGY, &mut map);
+    let mut names: Vec<&str> = Vec::new();
+    for name in query_params.split(',') {
+        if name == "all" {
+            names.extend(PARAMS.iter().map(|(name, _)| *name).chain(ALL_COMPOSITE_PARAMS));
+        } else {
+            names.push(name);
+        }
+    }
+
+    let mut requested: Vec<&(&str, Param)> = Vec::new();
+    let mut composites: Vec<&str> = Vec::new();
+    for name in names {
+        match find_param(name) {
+            Some(param) if !requested.iter().any(|(requested_name, _)| *requested_name == name) => requested.push(param),
+            Some(_) => {},
+            None if COMPOSITE_PARAMS.contains(&name) => {
+                if !composites.contains(&name) {
+                    composites.push(name);
+                }
+            },
+            None => println!("Unknown parameter: {}", name),
+        }
+    }
+
+    let mut regs: Vec<&dyn Register> = requested.iter().map(|(_, param)| param.register()).collect();
+    let needs_time_zone = requested.iter().any(|(_, param)| matches!(param, Param::Timestamp(_)));
+    if needs_time_zone {
+        regs.push(&registers::TIME_ZONE);
+    }
+    let mut values = inverter.read_many(&regs);
 
+    let time_zone = if needs_time_zone {
+        match values.pop().unwrap() {
+            Ok(RegisterValue::Numeric(time_zone)) => {
+                map.insert("time_zone".to_string(), Variant::Float(time_zone));
+                Some(time_zone)
+            },
+            Ok(RegisterValue::String(_)) => None,
+            Err(e) => {
+                println!("Error reading time_zone: {}", e);
+                None
+            }
+        }
     } else {
-        for param in query_params.split(",") {
-            match param {
-                "model_name" => {
-                    handle_string_reg(inverter, "model_name", &registers::MODEL_NAME, &mut map)
-                },
-                "serial_number" => {
-                    handle_string_reg(inverter, "serial_number", &registers::SERIAL_NUMBER, &mut map)
-                },
-                "input_power" => {
-                    handle_numeric_reg(inverter, "input_power", &registers::INPUT_POWER, &mut map)
-                },
-                "grid_voltage" => {
-                    handle_numeric_reg(inverter, "grid_voltage", &registers::GRID_VOLTAGE, &mut map)
-                },
-                "line_voltage_a_b" => {
-                    handle_numeric_reg(inverter, "line_voltage_a_b", &registers::LINE_VOLTAGE_A_B, &mut map)
-                },
-                "line_voltage_b_c" => {
-                    handle_numeric_reg(inverter, "line_voltage_b_c", &registers::LINE_VOLTAGE_B_C, &mut map)
-                },
-                "line_voltage_c_a" => {
-                    handle_numeric_reg(inverter, "line_voltage_c_a", &registers::LINE_VOLTAGE_C_A, &mut map)
-                },
-                "phase_a_voltage" => {
-                    handle_numeric_reg(inverter, "phase_a_voltage", &registers::PHASE_A_VOLTAGE, &mut map)
-                },
-                "phase_b_voltage" => {
-                    handle_numeric_reg(inverter, "phase_b_voltage", &registers::PHASE_B_VOLTAGE, &mut map)
-                },
-                "phase_c_voltage" => {
-                    handle_numeric_reg(inverter, "phase_c_voltage", &registers::PHASE_C_VOLTAGE, &mut map)
-                },
-                "grid_current" => {
-                    handle_numeric_reg(inverter, "grid_current", &registers::GRID_CURRENT, &mut map)
-                },
-                "phase_a_current" => {
-                    handle_numeric_reg(inverter, "phase_a_current", &registers::PHASE_A_CURRENT, &mut map)
-                },
-                "phase_b_current" => {
-                    handle_numeric_reg(inverter, "phase_b_current", &registers::PHASE_B_CURRENT, &mut map)
-                },
-                "phase_c_current" => {
-                    handle_numeric_reg(inverter, "phase_c_current", &registers::PHASE_C_CURRENT, &mut map)
-                },
-                "day_active_power_peak" => {
-                    handle_numeric_reg(inverter, "day_active_power_peak", &registers::DAY_ACTIVE_POWER_PEAK, &mut map)
-                },
-                "active_power" => {
-                    handle_numeric_reg(inverter, "active_power", &registers::ACTIVE_POWER, &mut map)
-                },
-                "reactive_power" => {
-                    handle_numeric_reg(inverter, "reactive_power", &registers::REACTIVE_POWER, &mut map)
-                },
-                "power_factor" => {
-                    handle_numeric_reg(inverter, "power_factor", &registers::POWER_FACTOR, &mut map)
-                },
-                "grid_frequency" => {
-                    handle_numeric_reg(inverter, "grid_frequency", &registers::GRID_FREQUENCY, &mut map)
-                },
-                "efficiency" => {
-                    handle_numeric_reg(inverter, "efficiency", &registers::EFFICIENCY, &mut map)
-                },
-                "internal_temperature" => {
-                    handle_numeric_reg(inverter, "internal_temperature", &registers::INTERNAL_TEMPERATURE, &mut map)
-                },
-                "insulation_resistance" => {
-                    handle_numeric_reg(inverter, "insulation_resistance", &registers::INSULATION_RESISTANCE, &mut map)
-                },
-                "device_status" => {
-                    handle_device_status(inverter, &mut map)
-                },
-                "startup_time" => {
-                    handle_timestamp_reg(inverter, "startup_time", &registers::STARTUP_TIME, &mut map)
-                },
-                "shutdown_time" => {
-                    handle_timestamp_reg(inverter, "shutdown_time", &registers::SHUTDOWN_TIME, &mut map)
-                },
-                "accumulated_yield_energy" => {
-                    handle_numeric_reg(inverter, "accumulated_yield_energy", &registers::ACCUMULATED_YIELD_ENERGY, &mut map)
-                },
-                "daily_yield_energy" => {
-                    handle_numeric_reg(inverter, "daily_yield_energy", &registers::DAILY_YIELD_ENERGY, &mut map)
-                },
-                _ => println!("Unknown parameter: {}", param),
+        None
+    };
+
+    for ((name, param), value) in requested.iter().zip(values) {
+        let value = match value {
+            Ok(value) => value,
+            Err(e) => {
+                println!("Error reading {}: {}", name, e);
+                continue;
             }
+        };
+        let variant = match (param, value) {
+            (Param::DeviceStatus(_), RegisterValue::Numeric(status)) => Variant::String(DeviceStatus::from_code(status as u16).to_string()),
+            (Param::FaultCode(_), RegisterValue::Numeric(code)) => Variant::FaultCode(describe_fault_code(code as u16)),
+            (Param::BatteryRunningStatus(_), RegisterValue::Numeric(code)) => Variant::String(BatteryRunningStatus::from_code(code as u16).to_string()),
+            (Param::BatteryWorkingMode(_), RegisterValue::Numeric(code)) => Variant::String(BatteryWorkingMode::from_code(code as u16).to_string()),
+            (Param::Timestamp(_), RegisterValue::Numeric(timestamp)) => match time_zone {
+                Some(time_zone) => Variant::Float(timestamp - 60.0 * time_zone),
+                None => continue
+            },
+            (_, RegisterValue::Numeric(value)) => Variant::Float(value),
+            (_, RegisterValue::String(value)) => Variant::String(value)
+        };
+        map.insert(name.to_string(), variant);
+    }
+
+    for name in composites {
+        match read_composite(inverter, name) {
+            Ok(variant) => {
+                map.insert(name.to_string(), variant);
+            },
+            Err(e) => println!("Error reading {}: {}", name, e)
         }
     }
-    
+
     map
-}
\ No newline at end of file
+}
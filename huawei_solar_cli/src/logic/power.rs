@@ -0,0 +1,16 @@
+use super::{exit_on_error, Target};
+use std::time::Duration;
+
+pub fn power(target: &Target, on: bool, timeout: Duration) {
+    with_inverter!(target, inverter => {
+        let status = if on {
+            println!("Starting up inverter, waiting up to {} s", timeout.as_secs());
+            inverter.power_on(timeout)
+        } else {
+            println!("Shutting down inverter, waiting up to {} s", timeout.as_secs());
+            inverter.power_off(timeout)
+        };
+        let status = exit_on_error(status, if on { "starting up inverter" } else { "shutting down inverter" });
+        println!("Device status: {}", status);
+    });
+}
@@ -1,4 +1,4 @@
-use crate::logic::params_parse::Variant;
+use crate::logic::params_parse::{or_unavailable, Variant};
 use std::collections::BTreeMap;
 use chrono::prelude::*;
 
@@ -41,10 +41,89 @@ pub fn print(map: &BTreeMap<String, Variant>) -> String {
             "internal_temperature" => output.push_str(&format!("Internal temperature: {} °C\n", value)),
             "insulation_resistance" => output.push_str(&format!("Insulation resistance: {} MΩ\n", value)),
             "device_status" => output.push_str(&format!("Device status: {}\n", value)),
+            "fault_code" => {
+                if let Variant::FaultCode(fault) = value {
+                    output.push_str(&format!("Fault code: {} ({})\n", fault.code, fault.description));
+                    if !fault.suggested_action.is_empty() {
+                        output.push_str(&format!("Suggested action: {}\n", fault.suggested_action));
+                    }
+                }
+            },
             "startup_time" => output.push_str(&format!("Startup time: {} (inverter's time)\n", timestamp_to_str(value))),
             "shutdown_time" => output.push_str(&format!("Shutdown time: {} (inverter's time)\n", timestamp_to_str(value))),
             "accumulated_yield_energy" => output.push_str(&format!("Accumulated yield energy: {} kWh\n", value)),
             "daily_yield_energy" => output.push_str(&format!("Daily yield energy: {} kWh\n", value)),
+            "optimizers_total" => output.push_str(&format!("Optimizers: {}\n", value)),
+            "optimizers_online" => output.push_str(&format!("Optimizers online: {}\n", value)),
+            "battery_state_of_charge" => output.push_str(&format!("Battery state of charge: {} %\n", value)),
+            "battery_charge_discharge_power" => output.push_str(&format!("Battery charge/discharge power: {} W\n", value)),
+            "battery_bus_voltage" => output.push_str(&format!("Battery bus voltage: {} V\n", value)),
+            "battery_bus_current" => output.push_str(&format!("Battery bus current: {} A\n", value)),
+            "battery_running_status" => output.push_str(&format!("Battery running status: {}\n", value)),
+            "battery_working_mode" => output.push_str(&format!("Battery working mode: {}\n", value)),
+            "battery_rated_capacity" => output.push_str(&format!("Battery rated capacity: {} Wh\n", value)),
+            "battery_day_charge_energy" => output.push_str(&format!("Battery daily charge energy: {} kWh\n", value)),
+            "battery_day_discharge_energy" => output.push_str(&format!("Battery daily discharge energy: {} kWh\n", value)),
+            "battery_total_charge_energy" => output.push_str(&format!("Battery total charge energy: {} kWh\n", value)),
+            "battery_total_discharge_energy" => output.push_str(&format!("Battery total discharge energy: {} kWh\n", value)),
+            "battery_unit_1_temperature" => output.push_str(&format!("Battery unit 1 temperature: {} °C\n", value)),
+            "battery_unit_2_temperature" => output.push_str(&format!("Battery unit 2 temperature: {} °C\n", value)),
+            "battery" => {
+                if let Variant::Battery(battery) = value {
+                    output.push_str(&format!("Battery: {} %, {} W, {}, {}\n", battery.state_of_charge, battery.charge_discharge_power, battery.running_status, battery.working_mode));
+                    for unit in &battery.units {
+                        output.push_str(&format!("Battery unit {} ({}): {} %, {} W, {} °C\n", unit.index, unit.serial_number, unit.state_of_charge, unit.charge_discharge_power, unit.temperature));
+                        for pack in &unit.packs {
+                            output.push_str(&format!("  Pack {} ({}): {} %, {} V, {} A, {}-{} °C\n", pack.index, pack.serial_number, pack.state_of_charge, pack.voltage, pack.current, pack.min_temperature, pack.max_temperature));
+                        }
+                    }
+                }
+            },
+            "device_info" => {
+                if let Variant::DeviceInfo(info) = value {
+                    output.push_str(&format!("Model: {} (ID {})\n", info.model_name, info.model_id));
+                    output.push_str(&format!("Serial number: {}, PN: {}\n", info.serial_number, info.product_number));
+                    output.push_str(&format!("Firmware version: {}\n", info.firmware_version));
+                    output.push_str(&format!("Software version: {}\n", info.software_version));
+                    output.push_str(&format!("Protocol version: {}.{}\n", info.protocol_version >> 16, info.protocol_version & 0xFFFF));
+                    output.push_str(&format!("PV strings: {}, MPP trackers: {}\n", info.number_of_pv_strings, info.number_of_mpp_trackers));
+                    output.push_str(&format!("Rated power: {} W\n", info.rated_power));
+                    output.push_str(&format!("Max active power: {} W\n", info.max_active_power));
+                    output.push_str(&format!("Max apparent power: {} VA\n", info.max_apparent_power));
+                    output.push_str(&format!("Max reactive power: {} var to grid, {} var from grid\n", info.max_reactive_power_to_grid, info.max_reactive_power_from_grid));
+                }
+            },
+            "power_meter" => {
+                if let Variant::PowerMeter(meter) = value {
+                    output.push_str(&format!("Power meter: {}, {}\n", if meter.online { "online" } else { "offline" }, meter.meter_type));
+                    output.push_str(&format!("Power meter active power: {} W\n", meter.active_power));
+                    output.push_str(&format!("Power meter reactive power: {} var\n", meter.reactive_power));
+                    output.push_str(&format!("Power meter power factor: {}\n", meter.power_factor));
+                    output.push_str(&format!("Power meter frequency: {} Hz\n", meter.frequency));
+                    for (phase, name) in meter.phases.iter().zip(["A", "B", "C"]) {
+                        output.push_str(&format!("Power meter phase {}: {} V, {} A, {} W\n", name, phase.voltage, phase.current, phase.active_power));
+                    }
+                    output.push_str(&format!("Power meter exported energy: {} kWh\n", meter.exported_energy));
+                    output.push_str(&format!("Power meter imported energy: {} kWh\n", meter.imported_energy));
+                }
+            },
+            "pv_strings" => {
+                if let Variant::PvStrings(strings) = value {
+                    for s in strings {
+                        output.push_str(&format!("PV{}: {} V, {} A, {} W\n", s.index, or_unavailable(s.voltage), or_unavailable(s.current), or_unavailable(s.power)));
+                    }
+                }
+            },
+            "alarms" => {
+                if let Variant::Alarms(alarms) = value {
+                    if alarms.is_empty() {
+                        output.push_str("Alarms: none\n");
+                    }
+                    for alarm in alarms {
+                        output.push_str(&format!("Alarm: {}\n", alarm));
+                    }
+                }
+            },
             &_ => {}
         }
     }
@@ -0,0 +1,34 @@
+use super::{exit_on_error, Target};
+use huawei_solar_rs::{registers, HuaweiSolar, ReactivePowerControl, RegisterTransport};
+
+// Accepts "pf=<power factor>" and "qs=<Q/S ratio>".
+pub fn parse_control(value: &str) -> Option<ReactivePowerControl> {
+    let (kind, number) = value.split_once('=')?;
+    let number = number.trim().parse::<f64>().ok()?;
+    match kind.trim() {
+        "pf" => Some(ReactivePowerControl::PowerFactor(number)),
+        "qs" => Some(ReactivePowerControl::QsRatio(number)),
+        _ => None
+    }
+}
+
+pub fn show(target: &Target) {
+    with_inverter!(target, inverter => print_control(&mut inverter));
+}
+
+pub fn set(target: &Target, control: ReactivePowerControl) {
+    with_inverter!(target, inverter => {
+        exit_on_error(inverter.set_reactive_power_control(control), "setting reactive power setpoint");
+        println!("Set reactive power setpoint to {}", control);
+        print_control(&mut inverter);
+    });
+}
+
+fn print_control<C: RegisterTransport>(inverter: &mut HuaweiSolar<C>) {
+    let setpoints = exit_on_error(inverter.read_reactive_power_setpoints(), "reading reactive power setpoints");
+    println!("Power factor setpoint: {}, Q/S ratio setpoint: {}", setpoints.power_factor, setpoints.qs_ratio);
+    match (inverter.read_numeric_register(&registers::REACTIVE_POWER), inverter.read_numeric_register(&registers::POWER_FACTOR)) {
+        (Ok(reactive_power), Ok(power_factor)) => println!("Current reactive power: {} var, power factor: {}", reactive_power, power_factor),
+        (Err(e), _) | (_, Err(e)) => println!("Current reactive power: not available ({})", e)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::logic::params_parse;
+use huawei_solar_rs::simulator::Simulator;
+use huawei_solar_rs::{registers, ExceptionCode, Register, RegisterValue};
+use std::time;
+
+pub fn run(listen: &str, slave_ids: &[u8], latency_ms: u64, drop_after: Option<usize>, values: &[String], exceptions: &[String]) {
+    let mut simulator = Simulator::new();
+    simulator.slave_ids(slave_ids).latency(time::Duration::from_millis(latency_ms));
+    // Startup and shutdown take effect right away.
+    simulator
+        .on_write(registers::STARTUP.addr(), registers::DEVICE_STATUS.addr(), &[0x0200])
+        .on_write(registers::SHUTDOWN.addr(), registers::DEVICE_STATUS.addr(), &[0x0301]);
+    if let Some(requests) = drop_after {
+        simulator.drop_connections_after(requests);
+    }
+
+    for value in values {
+        let (name, value) = match value.split_once('=') {
+            Some(pair) => pair,
+            None => {
+                println!("Bad value: {} (expected name=value)", value);
+                continue;
+            }
+        };
+        let reg = match params_parse::find_register(name) {
+            Some(reg) => reg,
+            None => {
+                println!("Unknown parameter: {}", name);
+                continue;
+            }
+        };
+        let value = match value.parse::<f64>() {
+            Ok(number) => RegisterValue::Numeric(number),
+            Err(_) => RegisterValue::String(value.to_string())
+        };
+        if let Err(e) = simulator.set_value(reg, &value) {
+            println!("Error setting {}: {}", name, e);
+        }
+    }
+
+    for exception in exceptions {
+        let parsed = exception.split_once('=').and_then(|(addr, code)| Some((addr.parse::<u16>().ok()?, code.parse::<u8>().ok()?)));
+        match parsed {
+            Some((addr, code)) => {
+                simulator.inject_exception(addr, ExceptionCode::from_code(code));
+            },
+            None => println!("Bad exception: {} (expected address=code)", exception)
+        }
+    }
+
+    let handle = match simulator.start(listen) {
+        Ok(handle) => handle,
+        Err(e) => {
+            println!("Error starting simulator: {}", e);
+            std::process::exit(1);
+        }
+    };
+    println!("Simulating inverter on {} (slave ids: {:?})", handle.local_addr(), slave_ids);
+    handle.wait();
+}
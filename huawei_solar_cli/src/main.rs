@@ -1,7 +1,87 @@
 mod logic;
 
-use clap::{Arg, Command};
-use logic::OutputFormat;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use huawei_solar_rs::{ForcibleCommand, ForcibleTarget};
+use logic::clock::TimeZoneSource;
+use logic::{OutputFormat, Protocol, Target};
+use std::time::Duration;
+
+// Arguments shared by every subcommand talking to an inverter.
+fn connection_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("ip_port")
+                .help("ip:[port] of the inverter (or S-Dongle if Modbus TCP is enabled, default port when no specified: 502), prefix with rtu+tcp:// for serial-to-Ethernet converters")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::new("slave_id")
+                .long("slave_id")
+                .help("Modbus slave id (default: 0) (specify 1 for connecting through S-Dongle)"),
+        )
+}
+
+fn power_args(command: Command) -> Command {
+    connection_args(command)
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .action(ArgAction::SetTrue)
+                .help("Confirm sending the command"),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .help("Seconds to wait for the inverter to reach the new state (default: 120)"),
+        )
+}
+
+fn forcible_args(command: Command) -> Command {
+    connection_args(command)
+        .arg(
+            Arg::new("power")
+                .long("power")
+                .required(true)
+                .help("Charge or discharge power in W"),
+        )
+        .arg(
+            Arg::new("minutes")
+                .long("minutes")
+                .conflicts_with("soc")
+                .required_unless_present("soc")
+                .help("Stop after this many minutes"),
+        )
+        .arg(
+            Arg::new("soc")
+                .long("soc")
+                .help("Stop at this state of charge in %"),
+        )
+}
+
+fn parse_number(matches: &ArgMatches, name: &str) -> Option<f64> {
+    matches.get_one::<String>(name).map(|s| s.parse::<f64>().expect("Bad number!"))
+}
+
+fn parse_target(matches: &ArgMatches) -> Target {
+    let ip_port = matches.get_one::<String>("ip_port").unwrap();
+    let slave_id = if let Some(slave_id) = matches.get_one::<String>("slave_id") {
+        println!("Using slave id: {}", slave_id);
+        slave_id.parse::<u8>().expect("Bad slave id!")
+    } else {
+        println!("Using default slave id: 0");
+        0
+    };
+    let (protocol, ip_port) = if let Some(address) = ip_port.strip_prefix("rtu+tcp://") {
+        println!("Using Modbus RTU over TCP");
+        (Protocol::RtuOverTcp, address)
+    } else {
+        (Protocol::Tcp, ip_port.strip_prefix("tcp://").unwrap_or(ip_port))
+    };
+    let ip = ip_port.split(':').next().unwrap().to_string();
+    let port = ip_port.split(':').nth(1).unwrap_or("502").parse::<u16>().expect("Bad port!");
+    Target { protocol, ip, port, slave_id }
+}
 
 fn main() {
     let matches = Command::new("huawei_solar")
@@ -10,17 +90,11 @@ fn main() {
         .arg_required_else_help(true)
         .author("fildaw")
         .subcommand(
-            Command::new("query")
+            connection_args(Command::new("query"))
                 .about("Query the inverter")
-                .arg(
-                    Arg::new("ip_port")
-                        .help("ip:[port] of the inverter (or S-Dongle if Modbus TCP is enabled, default port when no specified: 502)")
-                        .required(true)
-                        .index(1),
-                )
                 .arg(
                     Arg::new("query_params")
-                        .help("Query parameters (comma separated, use 'all' to query all params), allowed: model_name,serial_number,input_power,grid_voltage,line_voltage_a_b,line_voltage_b_c,line_voltage_c_a,phase_a_voltage,phase_b_voltage,phase_c_voltage,phase_a_current,phase_b_current,phase_c_current,day_active_power_peak,active_power,reactive_power,power_factor,grid_frequency,efficiency,internal_temperature,insulation_resistance,device_status,startup_time,shutdown_time,accumulated_yield_energy,daily_yield_energy")
+                        .help("Query parameters (comma separated, 'all' queries all inverter params, add 'battery' or 'power_meter' for attached devices), allowed: model_name,serial_number,input_power,grid_voltage,line_voltage_a_b,line_voltage_b_c,line_voltage_c_a,phase_a_voltage,phase_b_voltage,phase_c_voltage,phase_a_current,phase_b_current,phase_c_current,day_active_power_peak,active_power,reactive_power,power_factor,grid_frequency,efficiency,internal_temperature,insulation_resistance,device_status,fault_code,startup_time,shutdown_time,accumulated_yield_energy,daily_yield_energy,optimizers_total,optimizers_online,battery_state_of_charge,battery_charge_discharge_power,battery_bus_voltage,battery_bus_current,battery_running_status,battery_working_mode,battery_rated_capacity,battery_day_charge_energy,battery_day_discharge_energy,battery_total_charge_energy,battery_total_discharge_energy,battery_unit_1_temperature,battery_unit_2_temperature,pv_strings,alarms,battery,power_meter,device_info")
                         .required(true)
                         .index(2),
                 )
@@ -35,17 +109,155 @@ fn main() {
                         .short('f')
                         .long("output_format")
                         .help("Output format (json, pretty_print)"),
+                ),
+        )
+        .subcommand(
+            Command::new("clock")
+                .about("Show or set the inverter's system clock")
+                .subcommand_required(true)
+                .subcommand(
+                    connection_args(Command::new("show"))
+                        .about("Show the inverter's time next to the host's"),
+                )
+                .subcommand(
+                    connection_args(Command::new("sync"))
+                        .about("Set the inverter's clock from the host clock")
+                        .arg(
+                            Arg::new("time_zone")
+                                .long("time_zone")
+                                .allow_negative_numbers(true)
+                                .help("UTC offset to set in minutes, or 'host' for the host's offset (default: keep the inverter's time zone)"),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("limit")
+                .about("Show or change active power and grid export limits")
+                .subcommand_required(true)
+                .subcommand(
+                    connection_args(Command::new("show"))
+                        .about("Show the configured limits and the effective active power limit"),
+                )
+                .subcommand(
+                    connection_args(Command::new("active"))
+                        .about("Derate the inverter's active power")
+                        .arg(
+                            Arg::new("limit")
+                                .help("Limit as percentage of the max active power (eg. 50%), in watts (eg. 3000W) or none")
+                                .required(true)
+                                .index(2),
+                        ),
+                )
+                .subcommand(
+                    connection_args(Command::new("export"))
+                        .about("Limit the power fed into the grid (requires a power meter)")
+                        .arg(
+                            Arg::new("limit")
+                                .help("unlimited, zero, percentage of the max active power (eg. 50%) or watts (eg. 3000W)")
+                                .required(true)
+                                .index(2),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("power")
+                .about("Start up or shut down the inverter")
+                .subcommand_required(true)
+                .subcommand(power_args(Command::new("on")).about("Start up the inverter"))
+                .subcommand(power_args(Command::new("off")).about("Shut down the inverter, which stops feeding into the grid")),
+        )
+        .subcommand(
+            Command::new("reactive")
+                .about("Show or change the reactive power setpoint")
+                .subcommand_required(true)
+                .subcommand(
+                    connection_args(Command::new("show"))
+                        .about("Show the reactive power setpoints"),
+                )
+                .subcommand(
+                    connection_args(Command::new("set"))
+                        .about("Change the power factor or Q/S ratio setpoint")
+                        .arg(
+                            Arg::new("setpoint")
+                                .help("pf=<power factor> (eg. pf=0.95, negative absorbs reactive power) or qs=<Q/S ratio>")
+                                .required(true)
+                                .allow_negative_numbers(true)
+                                .index(2),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("battery")
+                .about("Control LUNA2000 batteries")
+                .subcommand_required(true)
+                .subcommand(
+                    connection_args(Command::new("show"))
+                        .about("Show the working mode, limits and state of charge"),
+                )
+                .subcommand(
+                    connection_args(Command::new("mode"))
+                        .about("Set the working mode")
+                        .arg(
+                            Arg::new("mode")
+                                .help("adaptive, fixed_charge_discharge, self_consumption, time_of_use_lg, fully_fed_to_grid or time_of_use")
+                                .required(true)
+                                .index(2),
+                        ),
+                )
+                .subcommand(
+                    connection_args(Command::new("limits"))
+                        .about("Change charge/discharge power limits and end of charge/discharge state of charge")
+                        .arg(Arg::new("max_charge_power").long("max_charge_power").help("Max charge power in W"))
+                        .arg(Arg::new("max_discharge_power").long("max_discharge_power").help("Max discharge power in W"))
+                        .arg(Arg::new("charge_cutoff").long("charge_cutoff").help("End of charge state of charge in % (90-100)"))
+                        .arg(Arg::new("discharge_cutoff").long("discharge_cutoff").help("End of discharge state of charge in % (0-20)")),
+                )
+                .subcommand(forcible_args(Command::new("charge")).about("Forcibly charge the batteries"))
+                .subcommand(forcible_args(Command::new("discharge")).about("Forcibly discharge the batteries"))
+                .subcommand(
+                    connection_args(Command::new("stop"))
+                        .about("Stop a forcible charge or discharge"),
+                ),
+        )
+        .subcommand(
+            Command::new("simulate")
+                .about("Run a simulated inverter serving Modbus TCP, for testing")
+                .arg(
+                    Arg::new("listen")
+                        .long("listen")
+                        .help("ip:port to listen on (default: 127.0.0.1:5020)"),
                 )
                 .arg(
                     Arg::new("slave_id")
                         .long("slave_id")
-                        .help("Modbus slave id (default: 0) (specify 1 for connecting through S-Dongle)"),
+                        .help("Comma separated Modbus slave ids to answer (default: 0,1)"),
+                )
+                .arg(
+                    Arg::new("latency_ms")
+                        .long("latency_ms")
+                        .help("Delay before every response in milliseconds (default: 0)"),
+                )
+                .arg(
+                    Arg::new("drop_after")
+                        .long("drop_after")
+                        .help("Close each connection after this many requests"),
+                )
+                .arg(
+                    Arg::new("set")
+                        .long("set")
+                        .action(ArgAction::Append)
+                        .help("Override a parameter value, e.g. --set active_power=1200 (can be repeated)"),
+                )
+                .arg(
+                    Arg::new("exception")
+                        .long("exception")
+                        .action(ArgAction::Append)
+                        .help("Answer requests touching a register address with a Modbus exception code, e.g. --exception 32080=6 (can be repeated)"),
                 ),
         )
         .get_matches();
     match matches.subcommand() {
         Some(("query", query_matches)) => {
-            let ip_port = query_matches.get_one::<String>("ip_port").unwrap();
             let query_params = query_matches.get_one::<String>("query_params").unwrap();
             let output_destination = query_matches.get_one::<String>("output_destination").unwrap();
             let chosen_output_format = if let Some(output_format)= query_matches.get_one::<String>("output_format") {
@@ -58,16 +270,90 @@ fn main() {
                 println!("Using default (json) as output format");
                 OutputFormat::Json
             };
-            let slave_id = if let Some(slave_id) = query_matches.get_one::<String>("slave_id") {
-                println!("Using slave id: {}", slave_id);
-                slave_id.parse::<u8>().expect("Bad slave id!")
-            } else {
-                println!("Using default slave id: 0");
-                0
-            };
-            let ip = ip_port.split(":").next().unwrap();
-            let port = ip_port.split(":").nth(1).unwrap_or("502").parse::<u16>().expect("Bad port!");
-            logic::query(ip, port, slave_id, &query_params, chosen_output_format, &output_destination);
+            let target = parse_target(query_matches);
+            logic::query(&target, query_params, chosen_output_format, output_destination);
+        },
+        Some(("clock", clock_matches)) => match clock_matches.subcommand() {
+            Some(("show", show_matches)) => logic::clock::show(&parse_target(show_matches)),
+            Some(("sync", sync_matches)) => {
+                let time_zone = match sync_matches.get_one::<String>("time_zone").map(|s| s.as_str()) {
+                    None => TimeZoneSource::Inverter,
+                    Some("host") => TimeZoneSource::Host,
+                    Some(minutes) => TimeZoneSource::Minutes(minutes.parse::<i16>().expect("Bad time zone!"))
+                };
+                logic::clock::sync(&parse_target(sync_matches), time_zone);
+            },
+            _ => unreachable!(),
+        },
+        Some(("limit", limit_matches)) => match limit_matches.subcommand() {
+            Some(("show", show_matches)) => logic::limit::show(&parse_target(show_matches)),
+            Some(("active", active_matches)) => {
+                let limit = logic::limit::parse_active_limit(active_matches.get_one::<String>("limit").unwrap()).expect("Bad limit!");
+                logic::limit::set_active(&parse_target(active_matches), limit);
+            },
+            Some(("export", export_matches)) => {
+                let limit = logic::limit::parse_export_limit(export_matches.get_one::<String>("limit").unwrap()).expect("Bad limit!");
+                logic::limit::set_export(&parse_target(export_matches), limit);
+            },
+            _ => unreachable!(),
+        },
+        Some(("power", power_matches)) => {
+            let (command, command_matches) = power_matches.subcommand().unwrap();
+            if !command_matches.get_flag("yes") {
+                println!("Refusing to power {} the inverter without --yes", command);
+                std::process::exit(1);
+            }
+            let timeout = command_matches.get_one::<String>("timeout").map(|s| s.parse::<u64>().expect("Bad timeout!")).unwrap_or(120);
+            logic::power::power(&parse_target(command_matches), command == "on", Duration::from_secs(timeout));
+        },
+        Some(("reactive", reactive_matches)) => match reactive_matches.subcommand() {
+            Some(("show", show_matches)) => logic::reactive::show(&parse_target(show_matches)),
+            Some(("set", set_matches)) => {
+                let control = logic::reactive::parse_control(set_matches.get_one::<String>("setpoint").unwrap()).expect("Bad setpoint!");
+                logic::reactive::set(&parse_target(set_matches), control);
+            },
+            _ => unreachable!(),
+        },
+        Some(("battery", battery_matches)) => match battery_matches.subcommand() {
+            Some(("show", show_matches)) => logic::battery::show(&parse_target(show_matches)),
+            Some(("mode", mode_matches)) => {
+                let mode = logic::battery::parse_working_mode(mode_matches.get_one::<String>("mode").unwrap()).expect("Bad working mode!");
+                logic::battery::set_working_mode(&parse_target(mode_matches), mode);
+            },
+            Some(("limits", limits_matches)) => {
+                let changes = logic::battery::LimitChanges {
+                    max_charge_power: parse_number(limits_matches, "max_charge_power"),
+                    max_discharge_power: parse_number(limits_matches, "max_discharge_power"),
+                    charge_cutoff_soc: parse_number(limits_matches, "charge_cutoff"),
+                    discharge_cutoff_soc: parse_number(limits_matches, "discharge_cutoff")
+                };
+                logic::battery::set_limits(&parse_target(limits_matches), changes);
+            },
+            Some((command @ ("charge" | "discharge"), force_matches)) => {
+                let power = parse_number(force_matches, "power").unwrap();
+                let until = match force_matches.get_one::<String>("minutes") {
+                    Some(minutes) => ForcibleTarget::Minutes(minutes.parse::<u16>().expect("Bad duration!")),
+                    None => ForcibleTarget::Soc(parse_number(force_matches, "soc").unwrap())
+                };
+                let command = if command == "charge" {
+                    ForcibleCommand::Charge { power, until }
+                } else {
+                    ForcibleCommand::Discharge { power, until }
+                };
+                logic::battery::force(&parse_target(force_matches), command);
+            },
+            Some(("stop", stop_matches)) => logic::battery::force(&parse_target(stop_matches), ForcibleCommand::Stop),
+            _ => unreachable!(),
+        },
+        Some(("simulate", simulate_matches)) => {
+            let listen = simulate_matches.get_one::<String>("listen").map(|s| s.as_str()).unwrap_or("127.0.0.1:5020");
+            let slave_ids: Vec<u8> = simulate_matches.get_one::<String>("slave_id").map(|s| s.as_str()).unwrap_or("0,1")
+                .split(',').map(|id| id.parse::<u8>().expect("Bad slave id!")).collect();
+            let latency_ms = simulate_matches.get_one::<String>("latency_ms").map(|s| s.parse::<u64>().expect("Bad latency!")).unwrap_or(0);
+            let drop_after = simulate_matches.get_one::<String>("drop_after").map(|s| s.parse::<usize>().expect("Bad request count!"));
+            let values: Vec<String> = simulate_matches.get_many::<String>("set").unwrap_or_default().cloned().collect();
+            let exceptions: Vec<String> = simulate_matches.get_many::<String>("exception").unwrap_or_default().cloned().collect();
+            logic::simulate::run(listen, &slave_ids, latency_ms, drop_after, &values, &exceptions);
         },
         _ => unreachable!(),
     }
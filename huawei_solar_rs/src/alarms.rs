@@ -0,0 +1,108 @@
+use crate::{registers, BitfieldRegister, Error, HuaweiSolar, Register, RegisterTransport};
+use std::fmt;
+use AlarmSeverity::{Major, Minor, Warning};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum AlarmSeverity {
+    Major,
+    Minor,
+    Warning
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Alarm {
+    pub name: &'static str,
+    pub id: u16,
+    pub severity: AlarmSeverity
+}
+
+impl fmt::Display for AlarmSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlarmSeverity::Major => write!(f, "major"),
+            AlarmSeverity::Minor => write!(f, "minor"),
+            AlarmSeverity::Warning => write!(f, "warning")
+        }
+    }
+}
+
+impl fmt::Display for Alarm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (ID {}, {})", self.name, self.id, self.severity)
+    }
+}
+
+const fn alarm(name: &'static str, id: u16, severity: AlarmSeverity) -> Alarm {
+    Alarm { name, id, severity }
+}
+
+// Alarm register, bit mask and the alarm it signals, as listed in the SUN2000 Modbus interface
+// definitions.
+pub const ALARM_DEFINITIONS: [(&BitfieldRegister, u16, Alarm); 46] = [
+    (&registers::ALARM_1, 0x0001, alarm("High String Input Voltage", 2001, Major)),
+    (&registers::ALARM_1, 0x0002, alarm("DC Arc Fault", 2002, Major)),
+    (&registers::ALARM_1, 0x0004, alarm("String Reverse Connection", 2011, Major)),
+    (&registers::ALARM_1, 0x0008, alarm("String Current Backfeed", 2012, Warning)),
+    (&registers::ALARM_1, 0x0010, alarm("Abnormal String Power", 2013, Warning)),
+    (&registers::ALARM_1, 0x0020, alarm("AFCI Self-Check Fail", 2021, Major)),
+    (&registers::ALARM_1, 0x0040, alarm("Phase Wire Short-Circuited to PE", 2031, Major)),
+    (&registers::ALARM_1, 0x0080, alarm("Grid Loss", 2032, Major)),
+    (&registers::ALARM_1, 0x0100, alarm("Grid Undervoltage", 2033, Major)),
+    (&registers::ALARM_1, 0x0200, alarm("Grid Overvoltage", 2034, Major)),
+    (&registers::ALARM_1, 0x0400, alarm("Grid Voltage Imbalance", 2035, Major)),
+    (&registers::ALARM_1, 0x0800, alarm("Grid Overfrequency", 2036, Major)),
+    (&registers::ALARM_1, 0x1000, alarm("Grid Underfrequency", 2037, Major)),
+    (&registers::ALARM_1, 0x2000, alarm("Unstable Grid Frequency", 2038, Major)),
+    (&registers::ALARM_1, 0x4000, alarm("Output Overcurrent", 2039, Major)),
+    (&registers::ALARM_1, 0x8000, alarm("Output DC Component Overhigh", 2040, Major)),
+    (&registers::ALARM_2, 0x0001, alarm("Abnormal Residual Current", 2051, Major)),
+    (&registers::ALARM_2, 0x0002, alarm("Abnormal Grounding", 2061, Major)),
+    (&registers::ALARM_2, 0x0004, alarm("Low Insulation Resistance", 2062, Major)),
+    (&registers::ALARM_2, 0x0008, alarm("Overtemperature", 2063, Minor)),
+    (&registers::ALARM_2, 0x0010, alarm("Device Fault", 2064, Major)),
+    (&registers::ALARM_2, 0x0020, alarm("Upgrade Failed or Version Mismatch", 2065, Minor)),
+    (&registers::ALARM_2, 0x0040, alarm("License Expired", 2066, Warning)),
+    (&registers::ALARM_2, 0x0080, alarm("Faulty Monitoring Unit", 61440, Minor)),
+    (&registers::ALARM_2, 0x0100, alarm("Faulty Power Collector", 2067, Major)),
+    (&registers::ALARM_2, 0x0200, alarm("Battery Abnormal", 2068, Minor)),
+    (&registers::ALARM_2, 0x0400, alarm("Active Islanding", 2070, Major)),
+    (&registers::ALARM_2, 0x0800, alarm("Passive Islanding", 2071, Major)),
+    (&registers::ALARM_2, 0x1000, alarm("Transient AC Overvoltage", 2072, Major)),
+    (&registers::ALARM_2, 0x2000, alarm("Peripheral Port Short Circuit", 2075, Warning)),
+    (&registers::ALARM_2, 0x4000, alarm("Churn Output Overload", 2077, Major)),
+    (&registers::ALARM_2, 0x8000, alarm("Abnormal PV Module Configuration", 2080, Major)),
+    (&registers::ALARM_3, 0x0001, alarm("Optimizer Fault", 2081, Warning)),
+    (&registers::ALARM_3, 0x0002, alarm("Built-in PID Operation Abnormal", 2085, Minor)),
+    (&registers::ALARM_3, 0x0004, alarm("High Input String Voltage to Ground", 2014, Major)),
+    (&registers::ALARM_3, 0x0008, alarm("External Fan Abnormal", 2086, Major)),
+    (&registers::ALARM_3, 0x0010, alarm("Battery Reverse Connection", 2069, Major)),
+    (&registers::ALARM_3, 0x0020, alarm("On-grid/Off-grid Controller Abnormal", 2082, Major)),
+    (&registers::ALARM_3, 0x0040, alarm("PV String Loss", 2015, Warning)),
+    (&registers::ALARM_3, 0x0080, alarm("Internal Fan Abnormal", 2087, Major)),
+    (&registers::ALARM_3, 0x0100, alarm("DC Protection Unit Abnormal", 2088, Major)),
+    (&registers::ALARM_3, 0x0200, alarm("EL Unit Abnormal", 2089, Minor)),
+    (&registers::ALARM_3, 0x0400, alarm("Active Adjustment Instruction Abnormal", 2090, Major)),
+    (&registers::ALARM_3, 0x0800, alarm("Reactive Adjustment Instruction Abnormal", 2091, Major)),
+    (&registers::ALARM_3, 0x1000, alarm("CT Wiring Abnormal", 2092, Major)),
+    (&registers::ALARM_3, 0x2000, alarm("DC Arc Fault (clear manually)", 2003, Major)),
+];
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    // Returns the active alarms in catalog order. Bits without a known alarm are ignored.
+    pub fn read_alarms(&mut self) -> Result<Vec<Alarm>, Error> {
+        let regs: [&BitfieldRegister; 3] = [&registers::ALARM_1, &registers::ALARM_2, &registers::ALARM_3];
+        let first = regs[0].addr();
+        let words = self.read_words(first, regs[2].addr() + regs[2].count() - first)?;
+
+        let mut alarms = Vec::new();
+        for (reg, mask, alarm) in ALARM_DEFINITIONS.iter() {
+            let bits = reg.decode(&words[(reg.addr() - first) as usize..])?;
+            if bits & *mask as u32 != 0 {
+                alarms.push(*alarm);
+            }
+        }
+        Ok(alarms)
+    }
+}
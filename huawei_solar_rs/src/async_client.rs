@@ -0,0 +1,155 @@
+use crate::{batch, pdu, DeviceStatus};
+use crate::{ConnectionOptions, Error, NumericRegister, NumericRegisterTrait, Register, RegisterValue, StringRegister};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use std::future::Future;
+use std::io;
+use std::time::Duration;
+
+// Async counterpart of `HuaweiSolar`, speaking Modbus TCP over a tokio socket.
+pub struct AsyncHuaweiSolar {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+    tid: u16,
+    slave_id: u8,
+    options: ConnectionOptions,
+    ip: String,
+    port: u16,
+    // Set while a request frame is being written. If the write is cancelled or times out, part of
+    // the frame may be on the socket, which would garble every later request.
+    broken: bool
+}
+
+impl AsyncHuaweiSolar {
+    pub async fn new_connection(ip: &str, port: u16, slave_id: u8) -> Result<AsyncHuaweiSolar, Error> {
+        AsyncHuaweiSolar::new_connection_with_options(ip, port, slave_id, ConnectionOptions::default()).await
+    }
+
+    // Applies the settle delay, the timeouts and the keepalive of `options`. The async client does
+    // not pace, retry or reconnect on its own, so `request_delay`, `retries`, `retry_backoff` and
+    // `auto_reconnect` are ignored. After a lost connection every request fails until `reconnect`
+    // is called.
+    pub async fn new_connection_with_options(ip: &str, port: u16, slave_id: u8, options: ConnectionOptions) -> Result<AsyncHuaweiSolar, Error> {
+        let stream = open_stream(ip, port, &options).await?;
+        Ok(AsyncHuaweiSolar { stream, buffer: Vec::new(), tid: 0, slave_id, options, ip: ip.to_string(), port, broken: false })
+    }
+
+    pub async fn reconnect(&mut self) -> Result<(), Error> {
+        self.stream = open_stream(&self.ip, self.port, &self.options).await?;
+        self.buffer.clear();
+        self.broken = false;
+        Ok(())
+    }
+
+    pub async fn read_holding_registers(&mut self, addr: u16, count: u16) -> Result<Vec<u16>, Error> {
+        let request = pdu::read_holding_registers_request(addr, count);
+        let response = self.request(&request).await?;
+        pdu::parse_read_holding_registers_response(&response, count)
+    }
+
+    pub async fn read_numeric_register<R: Register + NumericRegisterTrait>(&mut self, reg: &R) -> Result<f64, Error> {
+        let words = self.read_holding_registers(reg.addr(), reg.count()).await?;
+        let values = NumericRegisterTrait::decode(reg, &words)?;
+        values.first().copied().ok_or(Error::ShortResponse { expected: 1, actual: 0 })
+    }
+
+    pub async fn read_string_register(&mut self, reg: &StringRegister) -> Result<String, Error> {
+        let words = self.read_holding_registers(reg.addr(), reg.count()).await?;
+        reg.decode(&words)
+    }
+
+    pub async fn read_device_status(&mut self, reg: &NumericRegister<u16>) -> Result<DeviceStatus, Error> {
+        let status = self.read_numeric_register(reg).await?;
+        Ok(DeviceStatus::from_code(status as u16))
+    }
+
+    // See `HuaweiSolar::read_many`.
+    pub async fn read_many(&mut self, regs: &[&dyn Register]) -> Vec<Result<RegisterValue, Error>> {
+        let mut batch = batch::BatchRead::new(regs);
+        for block in batch.blocks() {
+            let words = self.read_holding_registers(block.addr, block.count).await;
+            for i in batch.complete(&block, words) {
+                batch.set(i, self.read_register(regs[i]).await);
+            }
+        }
+        batch.finish()
+    }
+
+    async fn read_register(&mut self, reg: &dyn Register) -> Result<RegisterValue, Error> {
+        let words = self.read_holding_registers(reg.addr(), reg.count()).await?;
+        reg.decode(&words)
+    }
+
+    async fn request(&mut self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        if self.broken {
+            return Err(Error::Connection(io::Error::new(io::ErrorKind::NotConnected, "connection was lost, call reconnect")));
+        }
+        self.tid = self.tid.wrapping_add(1);
+        let mut frame = pdu::mbap_header(self.tid, self.slave_id, request.len()).to_vec();
+        frame.extend_from_slice(request);
+        self.broken = true;
+        with_timeout(self.options.write_timeout, self.stream.write_all(&frame)).await?;
+        self.broken = false;
+        let result = with_timeout(self.options.read_timeout, self.read_response()).await;
+        if let Err(Error::Connection(_)) = result {
+            self.broken = true;
+        }
+        result
+    }
+
+    // Incoming bytes are buffered across calls, so a request cancelled or timed out while waiting
+    // for its response leaves the stream in a consistent state. Its late response is skipped by
+    // the transaction id check.
+    async fn read_response(&mut self) -> Result<Vec<u8>, Error> {
+        loop {
+            while let Some((tid, response)) = self.take_frame()? {
+                if tid == self.tid {
+                    return Ok(response);
+                }
+            }
+            let mut chunk = [0u8; 260];
+            let n = self.stream.read(&mut chunk).await?;
+            if n == 0 {
+                return Err(Error::Connection(io::Error::from(io::ErrorKind::UnexpectedEof)));
+            }
+            self.buffer.extend_from_slice(&chunk[..n]);
+        }
+    }
+
+    fn take_frame(&mut self) -> Result<Option<(u16, Vec<u8>)>, Error> {
+        if self.buffer.len() < pdu::MBAP_HEADER_SIZE {
+            return Ok(None);
+        }
+        let mut header = [0u8; pdu::MBAP_HEADER_SIZE];
+        header.copy_from_slice(&self.buffer[..pdu::MBAP_HEADER_SIZE]);
+        let (tid, len) = match pdu::parse_mbap_header(&header) {
+            Ok(header) => header,
+            Err(e) => {
+                self.buffer.clear();
+                return Err(e);
+            }
+        };
+        if self.buffer.len() < pdu::MBAP_HEADER_SIZE + len {
+            return Ok(None);
+        }
+        let frame: Vec<u8> = self.buffer.drain(..pdu::MBAP_HEADER_SIZE + len).collect();
+        Ok(Some((tid, frame[pdu::MBAP_HEADER_SIZE..].to_vec())))
+    }
+}
+
+async fn open_stream(ip: &str, port: u16, options: &ConnectionOptions) -> Result<TcpStream, Error> {
+    let stream = with_timeout(options.connect_timeout, TcpStream::connect((ip, port))).await?;
+    stream.set_nodelay(true)?;
+    if let Some(idle) = options.keepalive {
+        socket2::SockRef::from(&stream).set_tcp_keepalive(&socket2::TcpKeepalive::new().with_time(idle))?;
+    }
+    tokio::time::sleep(options.settle_delay).await;
+    Ok(stream)
+}
+
+async fn with_timeout<T, E: Into<Error>, F: Future<Output = Result<T, E>>>(timeout: Option<Duration>, future: F) -> Result<T, Error> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future).await.map_err(|_| Error::Timeout)?.map_err(Into::into),
+        None => future.await.map_err(Into::into)
+    }
+}
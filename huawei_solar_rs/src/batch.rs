@@ -0,0 +1,92 @@
+use crate::{Error, Register, RegisterValue};
+
+// Modbus limits a single "read holding registers" request to 125 registers.
+pub const MAX_REGISTERS_PER_READ: u16 = 125;
+// Reading through a few unused registers is cheaper than another round trip to the inverter.
+const MAX_GAP: u16 = 4;
+
+pub struct ReadBlock {
+    pub addr: u16,
+    pub count: u16,
+    pub members: Vec<usize>
+}
+
+// Groups registers into as few contiguous reads as possible. `members` holds indices into `regs`.
+pub fn plan(regs: &[&dyn Register]) -> Vec<ReadBlock> {
+    let mut order: Vec<usize> = (0..regs.len()).collect();
+    order.sort_by_key(|&i| regs[i].addr());
+
+    let mut blocks: Vec<ReadBlock> = Vec::new();
+    for i in order {
+        let start = regs[i].addr() as u32;
+        let end = start + regs[i].count() as u32;
+        if let Some(block) = blocks.last_mut() {
+            let block_start = block.addr as u32;
+            let block_end = block_start + block.count as u32;
+            let new_end = end.max(block_end);
+            if start <= block_end + MAX_GAP as u32 && new_end - block_start <= MAX_REGISTERS_PER_READ as u32 {
+                block.count = (new_end - block_start) as u16;
+                block.members.push(i);
+                continue;
+            }
+        }
+        blocks.push(ReadBlock {
+            addr: regs[i].addr(),
+            count: regs[i].count(),
+            members: vec![i]
+        });
+    }
+    blocks
+}
+
+// Result bookkeeping of `read_many`, shared by the sync and async clients. They only differ in
+// how they read a block of registers.
+pub struct BatchRead<'a> {
+    regs: &'a [&'a dyn Register],
+    results: Vec<Option<Result<RegisterValue, Error>>>
+}
+
+impl<'a> BatchRead<'a> {
+    pub fn new(regs: &'a [&'a dyn Register]) -> BatchRead<'a> {
+        BatchRead { regs, results: regs.iter().map(|_| None).collect() }
+    }
+
+    // Blocks to read. A register too large for a single request fails right away.
+    pub fn blocks(&mut self) -> Vec<ReadBlock> {
+        let mut blocks = plan(self.regs);
+        blocks.retain(|block| {
+            if block.count > MAX_REGISTERS_PER_READ {
+                self.results[block.members[0]] = Some(Err(Error::UnsupportedRegister { addr: block.addr, count: block.count }));
+            }
+            block.count <= MAX_REGISTERS_PER_READ
+        });
+        blocks
+    }
+
+    // Decodes the members of a block read. When a read of several registers fails, one of them
+    // may be unsupported by this inverter, so the returned members have to be read one by one.
+    pub fn complete(&mut self, block: &ReadBlock, words: Result<Vec<u16>, Error>) -> Vec<usize> {
+        match words {
+            Ok(words) => {
+                for &i in &block.members {
+                    let start = (self.regs[i].addr() - block.addr) as usize;
+                    self.results[i] = Some(self.regs[i].decode(words.get(start..).unwrap_or(&[])));
+                }
+                Vec::new()
+            },
+            Err(e) if block.members.len() == 1 => {
+                self.results[block.members[0]] = Some(Err(e));
+                Vec::new()
+            },
+            Err(_) => block.members.clone()
+        }
+    }
+
+    pub fn set(&mut self, i: usize, result: Result<RegisterValue, Error>) {
+        self.results[i] = Some(result);
+    }
+
+    pub fn finish(self) -> Vec<Result<RegisterValue, Error>> {
+        self.results.into_iter().map(|r| r.unwrap()).collect()
+    }
+}
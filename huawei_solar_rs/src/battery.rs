@@ -0,0 +1,392 @@
+use crate::{expect_numeric, registers, Error, ExceptionCode, HuaweiSolar, NumericRegister, Register, RegisterTransport, StringRegister};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BatteryRunningStatus {
+    Offline,
+    Standby,
+    Running,
+    Fault,
+    SleepMode,
+    Unknown(u16)
+}
+
+impl BatteryRunningStatus {
+    pub fn from_code(code: u16) -> BatteryRunningStatus {
+        match code {
+            0 => BatteryRunningStatus::Offline,
+            1 => BatteryRunningStatus::Standby,
+            2 => BatteryRunningStatus::Running,
+            3 => BatteryRunningStatus::Fault,
+            4 => BatteryRunningStatus::SleepMode,
+            _ => BatteryRunningStatus::Unknown(code)
+        }
+    }
+}
+
+impl fmt::Display for BatteryRunningStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatteryRunningStatus::Offline => write!(f, "Offline"),
+            BatteryRunningStatus::Standby => write!(f, "Standby"),
+            BatteryRunningStatus::Running => write!(f, "Running"),
+            BatteryRunningStatus::Fault => write!(f, "Fault"),
+            BatteryRunningStatus::SleepMode => write!(f, "Sleep mode"),
+            BatteryRunningStatus::Unknown(code) => write!(f, "Unknown status {}", code)
+        }
+    }
+}
+
+// Working mode as reported by the storage unit (37006), which is more detailed than the
+// working mode setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BatteryWorkingMode {
+    None,
+    ForcibleChargeDischarge,
+    TimeOfUseLg,
+    FixedChargeDischarge,
+    MaximiseSelfConsumption,
+    FullyFedToGrid,
+    TimeOfUseLuna2000,
+    RemoteSchedulingMaximiseSelfUse,
+    RemoteSchedulingFullExcess,
+    RemoteSchedulingTimeOfUse,
+    AiEnergyManagement,
+    Unknown(u16)
+}
+
+impl BatteryWorkingMode {
+    pub fn from_code(code: u16) -> BatteryWorkingMode {
+        match code {
+            0 => BatteryWorkingMode::None,
+            1 => BatteryWorkingMode::ForcibleChargeDischarge,
+            2 => BatteryWorkingMode::TimeOfUseLg,
+            3 => BatteryWorkingMode::FixedChargeDischarge,
+            4 => BatteryWorkingMode::MaximiseSelfConsumption,
+            5 => BatteryWorkingMode::FullyFedToGrid,
+            6 => BatteryWorkingMode::TimeOfUseLuna2000,
+            7 => BatteryWorkingMode::RemoteSchedulingMaximiseSelfUse,
+            8 => BatteryWorkingMode::RemoteSchedulingFullExcess,
+            9 => BatteryWorkingMode::RemoteSchedulingTimeOfUse,
+            10 => BatteryWorkingMode::AiEnergyManagement,
+            _ => BatteryWorkingMode::Unknown(code)
+        }
+    }
+}
+
+impl fmt::Display for BatteryWorkingMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatteryWorkingMode::None => write!(f, "None"),
+            BatteryWorkingMode::ForcibleChargeDischarge => write!(f, "Forcible charge/discharge"),
+            BatteryWorkingMode::TimeOfUseLg => write!(f, "Time of use (LG)"),
+            BatteryWorkingMode::FixedChargeDischarge => write!(f, "Fixed charge/discharge"),
+            BatteryWorkingMode::MaximiseSelfConsumption => write!(f, "Maximise self consumption"),
+            BatteryWorkingMode::FullyFedToGrid => write!(f, "Fully fed to grid"),
+            BatteryWorkingMode::TimeOfUseLuna2000 => write!(f, "Time of use (LUNA2000)"),
+            BatteryWorkingMode::RemoteSchedulingMaximiseSelfUse => write!(f, "Remote scheduling, maximise self-use"),
+            BatteryWorkingMode::RemoteSchedulingFullExcess => write!(f, "Remote scheduling, full excess"),
+            BatteryWorkingMode::RemoteSchedulingTimeOfUse => write!(f, "Remote scheduling, time of use"),
+            BatteryWorkingMode::AiEnergyManagement => write!(f, "AI energy management and scheduling"),
+            BatteryWorkingMode::Unknown(code) => write!(f, "Unknown mode {}", code)
+        }
+    }
+}
+
+// Power is positive while charging and negative while discharging.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatteryStatus {
+    pub state_of_charge: f64,
+    pub charge_discharge_power: f64,
+    pub bus_voltage: f64,
+    pub bus_current: f64,
+    pub running_status: BatteryRunningStatus,
+    pub working_mode: BatteryWorkingMode,
+    pub rated_capacity: f64,
+    pub day_charge_energy: f64,
+    pub day_discharge_energy: f64,
+    pub total_charge_energy: f64,
+    pub total_discharge_energy: f64,
+    pub units: Vec<BatteryUnit>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatteryUnit {
+    pub index: usize,
+    pub serial_number: String,
+    pub state_of_charge: f64,
+    pub charge_discharge_power: f64,
+    pub bus_voltage: f64,
+    pub bus_current: f64,
+    pub temperature: f64,
+    pub running_status: BatteryRunningStatus,
+    pub day_charge_energy: f64,
+    pub day_discharge_energy: f64,
+    pub total_charge_energy: f64,
+    pub total_discharge_energy: f64,
+    pub packs: Vec<BatteryPack>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatteryPack {
+    pub index: usize,
+    pub serial_number: String,
+    pub state_of_charge: f64,
+    pub charge_discharge_power: f64,
+    pub voltage: f64,
+    pub current: f64,
+    pub max_temperature: f64,
+    pub min_temperature: f64,
+    pub total_charge_energy: f64,
+    pub total_discharge_energy: f64
+}
+
+struct UnitRegisters {
+    serial_number: &'static StringRegister,
+    state_of_charge: &'static NumericRegister<u16>,
+    charge_discharge_power: &'static NumericRegister<i32>,
+    bus_voltage: &'static NumericRegister<u16>,
+    bus_current: &'static NumericRegister<i16>,
+    temperature: &'static NumericRegister<i16>,
+    running_status: &'static NumericRegister<u16>,
+    day_charge_energy: &'static NumericRegister<u32>,
+    day_discharge_energy: &'static NumericRegister<u32>,
+    total_charge_energy: &'static NumericRegister<u32>,
+    total_discharge_energy: &'static NumericRegister<u32>,
+    packs: [PackRegisters; 3]
+}
+
+struct PackRegisters {
+    serial_number: &'static StringRegister,
+    state_of_charge: &'static NumericRegister<u16>,
+    charge_discharge_power: &'static NumericRegister<i32>,
+    voltage: &'static NumericRegister<u16>,
+    current: &'static NumericRegister<i16>,
+    max_temperature: &'static NumericRegister<i16>,
+    min_temperature: &'static NumericRegister<i16>,
+    total_charge_energy: &'static NumericRegister<u32>,
+    total_discharge_energy: &'static NumericRegister<u32>
+}
+
+const UNITS: [UnitRegisters; 2] = [
+    UnitRegisters {
+        serial_number: &registers::STORAGE_UNIT_1_SERIAL_NUMBER,
+        state_of_charge: &registers::STORAGE_UNIT_1_STATE_OF_CAPACITY,
+        charge_discharge_power: &registers::STORAGE_UNIT_1_CHARGE_DISCHARGE_POWER,
+        bus_voltage: &registers::STORAGE_UNIT_1_BUS_VOLTAGE,
+        bus_current: &registers::STORAGE_UNIT_1_BUS_CURRENT,
+        temperature: &registers::STORAGE_UNIT_1_TEMPERATURE,
+        running_status: &registers::STORAGE_UNIT_1_RUNNING_STATUS,
+        day_charge_energy: &registers::STORAGE_UNIT_1_DAY_CHARGE_ENERGY,
+        day_discharge_energy: &registers::STORAGE_UNIT_1_DAY_DISCHARGE_ENERGY,
+        total_charge_energy: &registers::STORAGE_UNIT_1_TOTAL_CHARGE_ENERGY,
+        total_discharge_energy: &registers::STORAGE_UNIT_1_TOTAL_DISCHARGE_ENERGY,
+        packs: [
+            PackRegisters {
+                serial_number: &registers::STORAGE_UNIT_1_PACK_1_SERIAL_NUMBER,
+                state_of_charge: &registers::STORAGE_UNIT_1_PACK_1_STATE_OF_CAPACITY,
+                charge_discharge_power: &registers::STORAGE_UNIT_1_PACK_1_CHARGE_DISCHARGE_POWER,
+                voltage: &registers::STORAGE_UNIT_1_PACK_1_VOLTAGE,
+                current: &registers::STORAGE_UNIT_1_PACK_1_CURRENT,
+                max_temperature: &registers::STORAGE_UNIT_1_PACK_1_MAX_TEMPERATURE,
+                min_temperature: &registers::STORAGE_UNIT_1_PACK_1_MIN_TEMPERATURE,
+                total_charge_energy: &registers::STORAGE_UNIT_1_PACK_1_TOTAL_CHARGE_ENERGY,
+                total_discharge_energy: &registers::STORAGE_UNIT_1_PACK_1_TOTAL_DISCHARGE_ENERGY
+            },
+            PackRegisters {
+                serial_number: &registers::STORAGE_UNIT_1_PACK_2_SERIAL_NUMBER,
+                state_of_charge: &registers::STORAGE_UNIT_1_PACK_2_STATE_OF_CAPACITY,
+                charge_discharge_power: &registers::STORAGE_UNIT_1_PACK_2_CHARGE_DISCHARGE_POWER,
+                voltage: &registers::STORAGE_UNIT_1_PACK_2_VOLTAGE,
+                current: &registers::STORAGE_UNIT_1_PACK_2_CURRENT,
+                max_temperature: &registers::STORAGE_UNIT_1_PACK_2_MAX_TEMPERATURE,
+                min_temperature: &registers::STORAGE_UNIT_1_PACK_2_MIN_TEMPERATURE,
+                total_charge_energy: &registers::STORAGE_UNIT_1_PACK_2_TOTAL_CHARGE_ENERGY,
+                total_discharge_energy: &registers::STORAGE_UNIT_1_PACK_2_TOTAL_DISCHARGE_ENERGY
+            },
+            PackRegisters {
+                serial_number: &registers::STORAGE_UNIT_1_PACK_3_SERIAL_NUMBER,
+                state_of_charge: &registers::STORAGE_UNIT_1_PACK_3_STATE_OF_CAPACITY,
+                charge_discharge_power: &registers::STORAGE_UNIT_1_PACK_3_CHARGE_DISCHARGE_POWER,
+                voltage: &registers::STORAGE_UNIT_1_PACK_3_VOLTAGE,
+                current: &registers::STORAGE_UNIT_1_PACK_3_CURRENT,
+                max_temperature: &registers::STORAGE_UNIT_1_PACK_3_MAX_TEMPERATURE,
+                min_temperature: &registers::STORAGE_UNIT_1_PACK_3_MIN_TEMPERATURE,
+                total_charge_energy: &registers::STORAGE_UNIT_1_PACK_3_TOTAL_CHARGE_ENERGY,
+                total_discharge_energy: &registers::STORAGE_UNIT_1_PACK_3_TOTAL_DISCHARGE_ENERGY
+            }
+        ]
+    },
+    UnitRegisters {
+        serial_number: &registers::STORAGE_UNIT_2_SERIAL_NUMBER,
+        state_of_charge: &registers::STORAGE_UNIT_2_STATE_OF_CAPACITY,
+        charge_discharge_power: &registers::STORAGE_UNIT_2_CHARGE_DISCHARGE_POWER,
+        bus_voltage: &registers::STORAGE_UNIT_2_BUS_VOLTAGE,
+        bus_current: &registers::STORAGE_UNIT_2_BUS_CURRENT,
+        temperature: &registers::STORAGE_UNIT_2_TEMPERATURE,
+        running_status: &registers::STORAGE_UNIT_2_RUNNING_STATUS,
+        day_charge_energy: &registers::STORAGE_UNIT_2_DAY_CHARGE_ENERGY,
+        day_discharge_energy: &registers::STORAGE_UNIT_2_DAY_DISCHARGE_ENERGY,
+        total_charge_energy: &registers::STORAGE_UNIT_2_TOTAL_CHARGE_ENERGY,
+        total_discharge_energy: &registers::STORAGE_UNIT_2_TOTAL_DISCHARGE_ENERGY,
+        packs: [
+            PackRegisters {
+                serial_number: &registers::STORAGE_UNIT_2_PACK_1_SERIAL_NUMBER,
+                state_of_charge: &registers::STORAGE_UNIT_2_PACK_1_STATE_OF_CAPACITY,
+                charge_discharge_power: &registers::STORAGE_UNIT_2_PACK_1_CHARGE_DISCHARGE_POWER,
+                voltage: &registers::STORAGE_UNIT_2_PACK_1_VOLTAGE,
+                current: &registers::STORAGE_UNIT_2_PACK_1_CURRENT,
+                max_temperature: &registers::STORAGE_UNIT_2_PACK_1_MAX_TEMPERATURE,
+                min_temperature: &registers::STORAGE_UNIT_2_PACK_1_MIN_TEMPERATURE,
+                total_charge_energy: &registers::STORAGE_UNIT_2_PACK_1_TOTAL_CHARGE_ENERGY,
+                total_discharge_energy: &registers::STORAGE_UNIT_2_PACK_1_TOTAL_DISCHARGE_ENERGY
+            },
+            PackRegisters {
+                serial_number: &registers::STORAGE_UNIT_2_PACK_2_SERIAL_NUMBER,
+                state_of_charge: &registers::STORAGE_UNIT_2_PACK_2_STATE_OF_CAPACITY,
+                charge_discharge_power: &registers::STORAGE_UNIT_2_PACK_2_CHARGE_DISCHARGE_POWER,
+                voltage: &registers::STORAGE_UNIT_2_PACK_2_VOLTAGE,
+                current: &registers::STORAGE_UNIT_2_PACK_2_CURRENT,
+                max_temperature: &registers::STORAGE_UNIT_2_PACK_2_MAX_TEMPERATURE,
+                min_temperature: &registers::STORAGE_UNIT_2_PACK_2_MIN_TEMPERATURE,
+                total_charge_energy: &registers::STORAGE_UNIT_2_PACK_2_TOTAL_CHARGE_ENERGY,
+                total_discharge_energy: &registers::STORAGE_UNIT_2_PACK_2_TOTAL_DISCHARGE_ENERGY
+            },
+            PackRegisters {
+                serial_number: &registers::STORAGE_UNIT_2_PACK_3_SERIAL_NUMBER,
+                state_of_charge: &registers::STORAGE_UNIT_2_PACK_3_STATE_OF_CAPACITY,
+                charge_discharge_power: &registers::STORAGE_UNIT_2_PACK_3_CHARGE_DISCHARGE_POWER,
+                voltage: &registers::STORAGE_UNIT_2_PACK_3_VOLTAGE,
+                current: &registers::STORAGE_UNIT_2_PACK_3_CURRENT,
+                max_temperature: &registers::STORAGE_UNIT_2_PACK_3_MAX_TEMPERATURE,
+                min_temperature: &registers::STORAGE_UNIT_2_PACK_3_MIN_TEMPERATURE,
+                total_charge_energy: &registers::STORAGE_UNIT_2_PACK_3_TOTAL_CHARGE_ENERGY,
+                total_discharge_energy: &registers::STORAGE_UNIT_2_PACK_3_TOTAL_DISCHARGE_ENERGY
+            }
+        ]
+    }
+];
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    // Reads the combined storage values and every storage unit and battery pack present.
+    pub fn read_battery(&mut self) -> Result<BatteryStatus, Error> {
+        let regs: [&dyn Register; 11] = [
+            &registers::STORAGE_STATE_OF_CAPACITY,
+            &registers::STORAGE_CHARGE_DISCHARGE_POWER,
+            &registers::STORAGE_BUS_VOLTAGE,
+            &registers::STORAGE_BUS_CURRENT,
+            &registers::STORAGE_RUNNING_STATUS,
+            &registers::STORAGE_UNIT_1_WORKING_MODE,
+            &registers::STORAGE_RATED_CAPACITY,
+            &registers::STORAGE_DAY_CHARGE_ENERGY,
+            &registers::STORAGE_DAY_DISCHARGE_ENERGY,
+            &registers::STORAGE_TOTAL_CHARGE_ENERGY,
+            &registers::STORAGE_TOTAL_DISCHARGE_ENERGY
+        ];
+        let mut values = self.read_many(&regs).into_iter().map(expect_numeric);
+        let mut next = || values.next().unwrap();
+        let mut status = BatteryStatus {
+            state_of_charge: next()?,
+            charge_discharge_power: next()?,
+            bus_voltage: next()?,
+            bus_current: next()?,
+            running_status: BatteryRunningStatus::from_code(next()? as u16),
+            working_mode: BatteryWorkingMode::from_code(next()? as u16),
+            rated_capacity: next()?,
+            day_charge_energy: next()?,
+            day_discharge_energy: next()?,
+            total_charge_energy: next()?,
+            total_discharge_energy: next()?,
+            units: Vec::new()
+        };
+
+        for (i, unit) in UNITS.iter().enumerate() {
+            if let Some(serial_number) = self.read_serial_number(unit.serial_number)? {
+                status.units.push(self.read_battery_unit(i + 1, serial_number, unit)?);
+            }
+        }
+        Ok(status)
+    }
+
+    fn read_battery_unit(&mut self, index: usize, serial_number: String, unit: &UnitRegisters) -> Result<BatteryUnit, Error> {
+        let regs: [&dyn Register; 10] = [
+            unit.state_of_charge,
+            unit.charge_discharge_power,
+            unit.bus_voltage,
+            unit.bus_current,
+            unit.temperature,
+            unit.running_status,
+            unit.day_charge_energy,
+            unit.day_discharge_energy,
+            unit.total_charge_energy,
+            unit.total_discharge_energy
+        ];
+        let mut values = self.read_many(&regs).into_iter().map(expect_numeric);
+        let mut next = || values.next().unwrap();
+        let mut result = BatteryUnit {
+            index,
+            serial_number,
+            state_of_charge: next()?,
+            charge_discharge_power: next()?,
+            bus_voltage: next()?,
+            bus_current: next()?,
+            temperature: next()?,
+            running_status: BatteryRunningStatus::from_code(next()? as u16),
+            day_charge_energy: next()?,
+            day_discharge_energy: next()?,
+            total_charge_energy: next()?,
+            total_discharge_energy: next()?,
+            packs: Vec::new()
+        };
+
+        for (i, pack) in unit.packs.iter().enumerate() {
+            if let Some(serial_number) = self.read_serial_number(pack.serial_number)? {
+                result.packs.push(self.read_battery_pack(i + 1, serial_number, pack)?);
+            }
+        }
+        Ok(result)
+    }
+
+    fn read_battery_pack(&mut self, index: usize, serial_number: String, pack: &PackRegisters) -> Result<BatteryPack, Error> {
+        let regs: [&dyn Register; 8] = [
+            pack.state_of_charge,
+            pack.charge_discharge_power,
+            pack.voltage,
+            pack.current,
+            pack.max_temperature,
+            pack.min_temperature,
+            pack.total_charge_energy,
+            pack.total_discharge_energy
+        ];
+        let mut values = self.read_many(&regs).into_iter().map(expect_numeric);
+        let mut next = || values.next().unwrap();
+        Ok(BatteryPack {
+            index,
+            serial_number,
+            state_of_charge: next()?,
+            charge_discharge_power: next()?,
+            voltage: next()?,
+            current: next()?,
+            max_temperature: next()?,
+            min_temperature: next()?,
+            total_charge_energy: next()?,
+            total_discharge_energy: next()?
+        })
+    }
+
+    // Units and packs that are not installed have an empty serial number, or the address is
+    // rejected as illegal. Other exceptions, such as a busy dongle, are real errors.
+    fn read_serial_number(&mut self, reg: &StringRegister) -> Result<Option<String>, Error> {
+        match self.read_string_register(reg) {
+            Ok(serial_number) if serial_number.trim().is_empty() => Ok(None),
+            Ok(serial_number) => Ok(Some(serial_number)),
+            Err(Error::Exception(ExceptionCode::IllegalDataAddress)) => Ok(None),
+            Err(e) => Err(e)
+        }
+    }
+}
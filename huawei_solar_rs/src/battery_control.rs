@@ -0,0 +1,182 @@
+use crate::{expect_numeric, registers, Error, HuaweiSolar, Register, RegisterTransport};
+use std::fmt;
+
+// Working mode setting of the storage system (47086). What the storage units actually do is
+// reported as `BatteryWorkingMode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BatteryWorkingModeSetting {
+    Adaptive,
+    FixedChargeDischarge,
+    MaximiseSelfConsumption,
+    TimeOfUseLg,
+    FullyFedToGrid,
+    TimeOfUseLuna2000,
+    Unknown(u16)
+}
+
+impl BatteryWorkingModeSetting {
+    pub fn from_code(code: u16) -> BatteryWorkingModeSetting {
+        match code {
+            0 => BatteryWorkingModeSetting::Adaptive,
+            1 => BatteryWorkingModeSetting::FixedChargeDischarge,
+            2 => BatteryWorkingModeSetting::MaximiseSelfConsumption,
+            3 => BatteryWorkingModeSetting::TimeOfUseLg,
+            4 => BatteryWorkingModeSetting::FullyFedToGrid,
+            5 => BatteryWorkingModeSetting::TimeOfUseLuna2000,
+            _ => BatteryWorkingModeSetting::Unknown(code)
+        }
+    }
+
+    pub fn code(&self) -> u16 {
+        match self {
+            BatteryWorkingModeSetting::Adaptive => 0,
+            BatteryWorkingModeSetting::FixedChargeDischarge => 1,
+            BatteryWorkingModeSetting::MaximiseSelfConsumption => 2,
+            BatteryWorkingModeSetting::TimeOfUseLg => 3,
+            BatteryWorkingModeSetting::FullyFedToGrid => 4,
+            BatteryWorkingModeSetting::TimeOfUseLuna2000 => 5,
+            BatteryWorkingModeSetting::Unknown(code) => *code
+        }
+    }
+}
+
+impl fmt::Display for BatteryWorkingModeSetting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatteryWorkingModeSetting::Adaptive => write!(f, "Adaptive"),
+            BatteryWorkingModeSetting::FixedChargeDischarge => write!(f, "Fixed charge/discharge"),
+            BatteryWorkingModeSetting::MaximiseSelfConsumption => write!(f, "Maximise self consumption"),
+            BatteryWorkingModeSetting::TimeOfUseLg => write!(f, "Time of use (LG)"),
+            BatteryWorkingModeSetting::FullyFedToGrid => write!(f, "Fully fed to grid"),
+            BatteryWorkingModeSetting::TimeOfUseLuna2000 => write!(f, "Time of use (LUNA2000)"),
+            BatteryWorkingModeSetting::Unknown(code) => write!(f, "Unknown mode {}", code)
+        }
+    }
+}
+
+// Power in W, cutoffs as state of charge in %: charging stops at `charge_cutoff_soc` and
+// discharging at `discharge_cutoff_soc`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatteryLimits {
+    pub max_charge_power: f64,
+    pub max_discharge_power: f64,
+    pub charge_cutoff_soc: f64,
+    pub discharge_cutoff_soc: f64
+}
+
+// When a forcible charge or discharge ends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ForcibleTarget {
+    Minutes(u16),
+    Soc(f64)
+}
+
+// Charges or discharges the batteries with `power` W regardless of the working mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ForcibleCommand {
+    Stop,
+    Charge { power: f64, until: ForcibleTarget },
+    Discharge { power: f64, until: ForcibleTarget }
+}
+
+impl fmt::Display for ForcibleTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ForcibleTarget::Minutes(minutes) => write!(f, "for {} min", minutes),
+            ForcibleTarget::Soc(soc) => write!(f, "until {} %", soc)
+        }
+    }
+}
+
+impl fmt::Display for ForcibleCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ForcibleCommand::Stop => write!(f, "stop"),
+            ForcibleCommand::Charge { power, until } => write!(f, "charge with {} W {}", power, until),
+            ForcibleCommand::Discharge { power, until } => write!(f, "discharge with {} W {}", power, until)
+        }
+    }
+}
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    pub fn read_battery_working_mode_setting(&mut self) -> Result<BatteryWorkingModeSetting, Error> {
+        let mode = self.read_numeric_register(&registers::STORAGE_WORKING_MODE_SETTING)?;
+        Ok(BatteryWorkingModeSetting::from_code(mode as u16))
+    }
+
+    pub fn set_battery_working_mode(&mut self, mode: BatteryWorkingModeSetting) -> Result<(), Error> {
+        self.write_register(&registers::STORAGE_WORKING_MODE_SETTING, mode.code() as f64)
+    }
+
+    pub fn read_battery_limits(&mut self) -> Result<BatteryLimits, Error> {
+        let regs: [&dyn Register; 4] = [
+            &registers::STORAGE_MAXIMUM_CHARGING_POWER,
+            &registers::STORAGE_MAXIMUM_DISCHARGING_POWER,
+            &registers::STORAGE_CHARGING_CUTOFF_CAPACITY,
+            &registers::STORAGE_DISCHARGING_CUTOFF_CAPACITY
+        ];
+        let mut values = self.read_many(&regs).into_iter().map(expect_numeric);
+        let mut next = || values.next().unwrap();
+        Ok(BatteryLimits {
+            max_charge_power: next()?,
+            max_discharge_power: next()?,
+            charge_cutoff_soc: next()?,
+            discharge_cutoff_soc: next()?
+        })
+    }
+
+    // All values are checked before the first one is written, so invalid limits change nothing.
+    pub fn set_battery_limits(&mut self, limits: &BatteryLimits) -> Result<(), Error> {
+        let powers = [
+            (&registers::STORAGE_MAXIMUM_CHARGING_POWER, limits.max_charge_power),
+            (&registers::STORAGE_MAXIMUM_DISCHARGING_POWER, limits.max_discharge_power)
+        ];
+        let cutoffs = [
+            (&registers::STORAGE_CHARGING_CUTOFF_CAPACITY, limits.charge_cutoff_soc),
+            (&registers::STORAGE_DISCHARGING_CUTOFF_CAPACITY, limits.discharge_cutoff_soc)
+        ];
+        for (reg, value) in powers {
+            reg.check(value)?;
+        }
+        for (reg, value) in cutoffs {
+            reg.check(value)?;
+        }
+        for (reg, value) in powers {
+            self.write_register(reg, value)?;
+        }
+        for (reg, value) in cutoffs {
+            self.write_register(reg, value)?;
+        }
+        Ok(())
+    }
+
+    // The target and power are written first, then the command starts or stops the forcible
+    // charge or discharge. The command is sent once, without retries.
+    pub fn force_battery(&mut self, command: ForcibleCommand) -> Result<(), Error> {
+        let (code, power_register, power, until) = match command {
+            ForcibleCommand::Stop => return self.send_command(registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE.addr(), 0),
+            ForcibleCommand::Charge { power, until } => (1, &registers::STORAGE_FORCIBLE_CHARGE_POWER, power, until),
+            ForcibleCommand::Discharge { power, until } => (2, &registers::STORAGE_FORCIBLE_DISCHARGE_POWER, power, until)
+        };
+        match until {
+            ForcibleTarget::Minutes(minutes) => {
+                registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_PERIOD.check(minutes as f64)?;
+                power_register.check(power)?;
+                self.write_register(&registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_SETTING_MODE, 0.0)?;
+                self.write_register(&registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_PERIOD, minutes as f64)?;
+            },
+            ForcibleTarget::Soc(soc) => {
+                registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_SOC.check(soc)?;
+                power_register.check(power)?;
+                self.write_register(&registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_SETTING_MODE, 1.0)?;
+                self.write_register(&registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_SOC, soc)?;
+            }
+        }
+        self.write_register(power_register, power)?;
+        self.send_command(registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE.addr(), code)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{expect_numeric, registers, Error, HuaweiSolar, Register, RegisterTransport, RegisterValue};
+use chrono::{DateTime, FixedOffset, Offset, TimeZone};
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    // The inverter keeps local time, so the time zone is needed to get at the actual instant.
+    pub fn read_system_time(&mut self) -> Result<DateTime<FixedOffset>, Error> {
+        let regs: [&dyn Register; 2] = [&registers::SYSTEM_TIME, &registers::TIME_ZONE];
+        let mut values = self.read_many(&regs).into_iter().map(expect_numeric);
+        let local = values.next().unwrap()? as i64;
+        let time_zone = values.next().unwrap()? as i32;
+        let offset = FixedOffset::east_opt(time_zone * 60)
+            .ok_or_else(|| Error::InvalidResponse(format!("invalid time zone of {} minutes", time_zone)))?;
+        offset.timestamp_opt(local - offset.local_minus_utc() as i64, 0).single()
+            .ok_or_else(|| Error::InvalidResponse(format!("invalid system time {}", local)))
+    }
+
+    // Sets both the clock and the time zone, the latter from the offset of `time`. The time zone
+    // is only written when it changes. If the clock cannot be set after that, the error is
+    // wrapped in `SystemTimeNotSet`.
+    pub fn set_system_time<Tz: TimeZone>(&mut self, time: &DateTime<Tz>) -> Result<(), Error> {
+        let offset = time.offset().fix().local_minus_utc();
+        if offset % 60 != 0 {
+            return Err(Error::InvalidValue(format!("time zone offset of {} s is not a whole number of minutes", offset)));
+        }
+        let time_zone = (offset / 60) as f64;
+        registers::TIME_ZONE.check(time_zone)?;
+        let local = time.timestamp() + offset as i64;
+        if local < 0 || local > u32::MAX as i64 {
+            return Err(Error::InvalidValue(format!("{} cannot be represented by the inverter", time.naive_utc())));
+        }
+        let system_time = Register::encode(&registers::SYSTEM_TIME, &RegisterValue::Numeric(local as f64))?;
+
+        let zone_changed = self.read_numeric_register(&registers::TIME_ZONE)? != time_zone;
+        if zone_changed {
+            self.write_register(&registers::TIME_ZONE, time_zone)?;
+        }
+        match self.write_words(registers::SYSTEM_TIME.addr(), &system_time) {
+            Err(e) if zone_changed => Err(Error::SystemTimeNotSet(Box::new(e))),
+            result => result
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::Error;
+
+// Counters kept by `HuaweiSolar` since it was created.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    // Requests sent, including retries.
+    pub requests: u64,
+    // Requests that failed, including ones that were retried successfully afterwards.
+    pub failures: u64,
+    pub retries: u64,
+    pub reconnects: u64,
+    pub failed_reconnects: u64
+}
+
+#[derive(Debug)]
+pub enum ConnectionEvent<'a> {
+    // The connection is considered broken and will be reopened before the next request.
+    Lost(&'a Error),
+    Reconnected,
+    ReconnectFailed(&'a Error),
+    // A read or setting write is repeated after a transient error. `attempt` starts at 1.
+    Retry { attempt: u32, error: &'a Error }
+}
@@ -0,0 +1,167 @@
+use crate::DeviceStatus;
+use std::{fmt, io, str};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExceptionCode {
+    IllegalFunction,
+    IllegalDataAddress,
+    IllegalDataValue,
+    SlaveDeviceFailure,
+    Acknowledge,
+    SlaveDeviceBusy,
+    NegativeAcknowledge,
+    MemoryParityError,
+    GatewayPathUnavailable,
+    GatewayTargetFailedToRespond,
+    Unknown(u8)
+}
+
+impl ExceptionCode {
+    pub fn from_code(code: u8) -> ExceptionCode {
+        match code {
+            0x01 => ExceptionCode::IllegalFunction,
+            0x02 => ExceptionCode::IllegalDataAddress,
+            0x03 => ExceptionCode::IllegalDataValue,
+            0x04 => ExceptionCode::SlaveDeviceFailure,
+            0x05 => ExceptionCode::Acknowledge,
+            0x06 => ExceptionCode::SlaveDeviceBusy,
+            0x07 => ExceptionCode::NegativeAcknowledge,
+            0x08 => ExceptionCode::MemoryParityError,
+            0x0A => ExceptionCode::GatewayPathUnavailable,
+            0x0B => ExceptionCode::GatewayTargetFailedToRespond,
+            code => ExceptionCode::Unknown(code)
+        }
+    }
+
+    pub fn code(&self) -> u8 {
+        match self {
+            ExceptionCode::IllegalFunction => 0x01,
+            ExceptionCode::IllegalDataAddress => 0x02,
+            ExceptionCode::IllegalDataValue => 0x03,
+            ExceptionCode::SlaveDeviceFailure => 0x04,
+            ExceptionCode::Acknowledge => 0x05,
+            ExceptionCode::SlaveDeviceBusy => 0x06,
+            ExceptionCode::NegativeAcknowledge => 0x07,
+            ExceptionCode::MemoryParityError => 0x08,
+            ExceptionCode::GatewayPathUnavailable => 0x0A,
+            ExceptionCode::GatewayTargetFailedToRespond => 0x0B,
+            ExceptionCode::Unknown(code) => *code
+        }
+    }
+}
+
+impl fmt::Display for ExceptionCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExceptionCode::IllegalFunction => write!(f, "illegal function"),
+            ExceptionCode::IllegalDataAddress => write!(f, "illegal data address"),
+            ExceptionCode::IllegalDataValue => write!(f, "illegal data value"),
+            ExceptionCode::SlaveDeviceFailure => write!(f, "slave device failure"),
+            ExceptionCode::Acknowledge => write!(f, "acknowledge"),
+            ExceptionCode::SlaveDeviceBusy => write!(f, "slave device busy"),
+            ExceptionCode::NegativeAcknowledge => write!(f, "negative acknowledge"),
+            ExceptionCode::MemoryParityError => write!(f, "memory parity error"),
+            ExceptionCode::GatewayPathUnavailable => write!(f, "gateway path unavailable"),
+            ExceptionCode::GatewayTargetFailedToRespond => write!(f, "gateway target device failed to respond"),
+            ExceptionCode::Unknown(code) => write!(f, "unknown exception code 0x{:02X}", code)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    // The connection could not be established or was lost.
+    Connection(io::Error),
+    // The inverter did not answer in time.
+    Timeout,
+    // The inverter answered with a Modbus exception.
+    Exception(ExceptionCode),
+    // The response did not follow the Modbus protocol.
+    InvalidResponse(String),
+    // An RTU frame arrived with a wrong checksum.
+    CrcMismatch { expected: u16, actual: u16 },
+    // The response held fewer registers than the register needs.
+    ShortResponse { expected: usize, actual: usize },
+    // A string register did not contain valid UTF-8.
+    InvalidUtf8(str::Utf8Error),
+    // The register cannot be read with this API.
+    UnsupportedRegister { addr: u16, count: u16 },
+    // The value cannot be stored in the register.
+    InvalidValue(String),
+    // The inverter reported the "invalid value" sentinel (e.g. 0x7FFF for i16), meaning the
+    // quantity is not measured or not available on this model.
+    ValueUnavailable { addr: u16 },
+    // A written register read back a different value, so the inverter did not apply it.
+    WriteNotApplied { addr: u16, written: f64, read: f64 },
+    // The inverter did not reach the expected state in time after a command.
+    StatusTimeout(DeviceStatus),
+    // The inverter went into a fault state after a command.
+    DeviceFault(DeviceStatus),
+    // The time zone was changed, but writing the system time failed afterwards.
+    SystemTimeNotSet(Box<Error>)
+}
+
+impl Error {
+    // Whether repeating the same request may succeed.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::Connection(_) | Error::Timeout | Error::CrcMismatch { .. } => true,
+            Error::Exception(code) => matches!(code, ExceptionCode::SlaveDeviceBusy | ExceptionCode::Acknowledge
+                | ExceptionCode::GatewayPathUnavailable | ExceptionCode::GatewayTargetFailedToRespond),
+            _ => false
+        }
+    }
+
+    // Whether the stream can no longer be trusted: the peer is gone, or a late or garbled
+    // response may still be in flight and would be taken as the answer to the next request.
+    pub fn breaks_connection(&self) -> bool {
+        matches!(self, Error::Connection(_) | Error::Timeout | Error::InvalidResponse(_) | Error::CrcMismatch { .. })
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Connection(e) => write!(f, "connection error: {}", e),
+            Error::Timeout => write!(f, "request timed out"),
+            Error::Exception(code) => write!(f, "modbus exception: {}", code),
+            Error::InvalidResponse(reason) => write!(f, "invalid response: {}", reason),
+            Error::CrcMismatch { expected, actual } => write!(f, "CRC mismatch: expected 0x{:04X}, got 0x{:04X}", expected, actual),
+            Error::ShortResponse { expected, actual } => write!(f, "short response: expected {} registers, got {}", expected, actual),
+            Error::InvalidUtf8(e) => write!(f, "invalid UTF-8 in string register: {}", e),
+            Error::UnsupportedRegister { addr, count } => write!(f, "unsupported register at address {} ({} registers)", addr, count),
+            Error::InvalidValue(reason) => write!(f, "invalid value: {}", reason),
+            Error::ValueUnavailable { addr } => write!(f, "value of register {} is not available", addr),
+            Error::WriteNotApplied { addr, written, read } => write!(f, "register {} reads {} after writing {}", addr, read, written),
+            Error::StatusTimeout(status) => write!(f, "inverter still reports \"{}\" after waiting", status),
+            Error::DeviceFault(status) => write!(f, "inverter reports \"{}\"", status),
+            Error::SystemTimeNotSet(e) => write!(f, "time zone was changed, but setting the system time failed: {}", e)
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Connection(e) => Some(e),
+            Error::InvalidUtf8(e) => Some(e),
+            Error::SystemTimeNotSet(e) => Some(e.as_ref()),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        match e.kind() {
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => Error::Timeout,
+            _ => Error::Connection(e)
+        }
+    }
+}
+
+impl From<str::Utf8Error> for Error {
+    fn from(e: str::Utf8Error) -> Error {
+        Error::InvalidUtf8(e)
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{registers, Error, HuaweiSolar, RegisterTransport};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FaultCode {
+    pub code: u16,
+    pub description: String,
+    pub suggested_action: String
+}
+
+// Fault code, description and suggested action, after the alarm reference in the SUN2000
+// user manual. The fault code register holds the ID of the most severe active fault.
+pub const FAULT_CODE_DEFINITIONS: [(u16, &str, &str); 36] = [
+    (0, "No fault", ""),
+    (2001, "High string input voltage", "Check the string configuration; the open-circuit voltage of a string must not exceed the maximum input voltage."),
+    (2002, "DC arc fault", "Check the PV string cables and connectors for damage or loose contacts."),
+    (2003, "DC arc fault (clear manually)", "Check the PV string cables and connectors, then clear the alarm in the app."),
+    (2011, "String reverse connection", "Check the polarity of the PV string cables."),
+    (2012, "String current backfeed", "Check that the number of PV modules is the same in all strings of an MPPT."),
+    (2013, "Abnormal string power", "Check the strings for shading, dirt or damaged modules."),
+    (2014, "High input string voltage to ground", "Check the insulation of the PV strings to ground."),
+    (2015, "PV string loss", "Check the PV string fuses, cables and connectors."),
+    (2021, "AFCI self-check failure", "Restart the inverter. Contact your dealer if the fault persists."),
+    (2031, "Phase wire short-circuited to PE", "Check the impedance of the output phase wires to PE."),
+    (2032, "Grid loss", "Check the AC circuit breaker and the grid connection."),
+    (2033, "Grid undervoltage", "Check the grid voltage. If it is within range, check the AC cables."),
+    (2034, "Grid overvoltage", "Check the grid voltage and the impedance of the AC cables; ask the grid operator if it persists."),
+    (2035, "Grid voltage imbalance", "Check the grid phase voltages and the AC cable connections."),
+    (2036, "Grid overfrequency", "The inverter reconnects once the grid frequency is back in range; ask the grid operator if it persists."),
+    (2037, "Grid underfrequency", "The inverter reconnects once the grid frequency is back in range; ask the grid operator if it persists."),
+    (2038, "Unstable grid frequency", "The inverter reconnects once the grid recovers; ask the grid operator if it persists."),
+    (2039, "Output overcurrent", "Usually caused by a grid disturbance and recovers automatically. Contact your dealer if it occurs often."),
+    (2040, "Output DC component overhigh", "Usually caused by a grid disturbance and recovers automatically. Contact your dealer if it occurs often."),
+    (2051, "Abnormal residual current", "Check the insulation resistance of the PV strings to ground."),
+    (2061, "Abnormal grounding", "Check that the PE cable of the inverter is connected properly."),
+    (2062, "Low insulation resistance", "Check the PV strings and cables for ground faults, e.g. after rain."),
+    (2063, "Overtemperature", "Check ventilation and ambient temperature at the installation site."),
+    (2064, "Device fault", "Restart the inverter. Contact your dealer if the fault persists."),
+    (2065, "Upgrade failed or version mismatch", "Upgrade the firmware again."),
+    (2066, "License expired", "Apply for a new license and load it."),
+    (2067, "Faulty power collector", "Check the power meter connection and its communication settings."),
+    (2068, "Battery abnormal", "Check the battery status and its connection to the inverter."),
+    (2069, "Battery reverse connection", "Check the polarity of the battery cables."),
+    (2070, "Active islanding", "Check the grid connection. The inverter reconnects once the grid recovers."),
+    (2071, "Passive islanding", "Check the grid connection. The inverter reconnects once the grid recovers."),
+    (2072, "Transient AC overvoltage", "Check the grid voltage; ask the grid operator if it occurs often."),
+    (2080, "Abnormal PV module configuration", "Check the number of PV modules and optimizers in each string."),
+    (2081, "Optimizer fault", "Check the optimizer alarms in the app."),
+    (61440, "Faulty monitoring unit", "Restart the inverter. Contact your dealer if the fault persists."),
+];
+
+pub fn describe_fault_code(code: u16) -> FaultCode {
+    let (description, suggested_action) = match FAULT_CODE_DEFINITIONS.iter().find(|(c, _, _)| *c == code) {
+        Some((_, description, suggested_action)) => (*description, *suggested_action),
+        None => ("Unknown fault", "Contact your dealer or Huawei technical support.")
+    };
+    FaultCode { code, description: description.to_string(), suggested_action: suggested_action.to_string() }
+}
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    pub fn read_fault_code(&mut self) -> Result<FaultCode, Error> {
+        let code = self.read_numeric_register(&registers::FAULT_CODE)?;
+        Ok(describe_fault_code(code as u16))
+    }
+}
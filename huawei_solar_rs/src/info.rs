@@ -0,0 +1,62 @@
+use crate::{expect_numeric, expect_string, registers, Error, HuaweiSolar, Register, RegisterTransport};
+
+// Nameplate data, which does not change while the inverter is running.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceInfo {
+    pub model_name: String,
+    pub model_id: u16,
+    pub serial_number: String,
+    pub product_number: String,
+    pub firmware_version: String,
+    pub software_version: String,
+    pub protocol_version: u32,
+    pub number_of_pv_strings: u16,
+    pub number_of_mpp_trackers: u16,
+    pub rated_power: f64,
+    pub max_active_power: f64,
+    pub max_apparent_power: f64,
+    pub max_reactive_power_to_grid: f64,
+    pub max_reactive_power_from_grid: f64
+}
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    pub fn read_device_info(&mut self) -> Result<DeviceInfo, Error> {
+        let regs: [&dyn Register; 14] = [
+            &registers::MODEL_NAME,
+            &registers::SERIAL_NUMBER,
+            &registers::PRODUCT_NUMBER,
+            &registers::FIRMWARE_VERSION,
+            &registers::SOFTWARE_VERSION,
+            &registers::MODEL_ID,
+            &registers::PROTOCOL_VERSION,
+            &registers::NUMBER_OF_PV_STRINGS,
+            &registers::NUMBER_OF_MPP_TRACKERS,
+            &registers::RATED_POWER,
+            &registers::MAX_ACTIVE_POWER,
+            &registers::MAX_APPARENT_POWER,
+            &registers::MAX_REACTIVE_POWER_TO_GRID,
+            &registers::MAX_REACTIVE_POWER_FROM_GRID
+        ];
+        let mut values = self.read_many(&regs).into_iter();
+        let mut string = || expect_string(values.next().unwrap());
+        let (model_name, serial_number, product_number, firmware_version, software_version) = (string()?, string()?, string()?, string()?, string()?);
+        let mut number = || expect_numeric(values.next().unwrap());
+        Ok(DeviceInfo {
+            model_name,
+            model_id: number()? as u16,
+            serial_number,
+            product_number,
+            firmware_version,
+            software_version,
+            protocol_version: number()? as u32,
+            number_of_pv_strings: number()? as u16,
+            number_of_mpp_trackers: number()? as u16,
+            rated_power: number()?,
+            max_active_power: number()?,
+            max_apparent_power: number()?,
+            max_reactive_power_to_grid: number()?,
+            max_reactive_power_from_grid: number()?
+        })
+    }
+}
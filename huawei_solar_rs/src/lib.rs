@@ -1,13 +1,93 @@
-use modbus::{Client};
-use modbus::tcp;
 use std::marker::PhantomData;
+use std::net::TcpStream;
 use std::{thread, time, str};
 use byteorder::{WriteBytesExt, BigEndian};
 
 pub mod registers;
+pub mod simulator;
+mod alarms;
+mod battery;
+mod battery_control;
+mod batch;
+mod clock;
+mod connection;
+mod error;
+mod fault;
+mod transport;
+mod info;
+mod limit;
+mod meter;
+mod optimizer;
+mod options;
+mod pdu;
+mod power;
+mod pv;
+mod reactive;
+mod tcp;
+mod rtu;
+mod status;
+mod upload;
+#[cfg(feature = "tokio")]
+mod async_client;
+
+pub use alarms::{Alarm, AlarmSeverity, ALARM_DEFINITIONS};
+pub use battery::{BatteryPack, BatteryRunningStatus, BatteryStatus, BatteryUnit, BatteryWorkingMode};
+pub use battery_control::{BatteryLimits, BatteryWorkingModeSetting, ForcibleCommand, ForcibleTarget};
+pub use connection::{ConnectionEvent, ConnectionStats};
+pub use error::{Error, ExceptionCode};
+pub use fault::{describe_fault_code, FaultCode, FAULT_CODE_DEFINITIONS};
+pub use transport::RegisterTransport;
+pub use info::DeviceInfo;
+pub use limit::{ActivePowerLimit, ExportLimit, PowerLimit};
+pub use meter::{MeterPhase, MeterType, PowerMeter};
+pub use optimizer::{parse_optimizer_data, OptimizerReading, OptimizerSummary};
+pub use options::ConnectionOptions;
+pub use pv::PvString;
+pub use reactive::{ReactivePowerControl, ReactivePowerSetpoints};
+pub use tcp::TcpTransport;
+pub use rtu::RtuTransport;
+pub use status::DeviceStatus;
+#[cfg(feature = "serial")]
+pub use rtu::{Parity, SerialConfig, StopBits};
+#[cfg(feature = "tokio")]
+pub use async_client::AsyncHuaweiSolar;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegisterValue {
+    Numeric(f64),
+    String(String)
+}
+
+pub trait Register: Sync {
+    fn addr(&self) -> u16;
+    fn count(&self) -> u16;
+    fn decode(&self, words: &[u16]) -> Result<RegisterValue, Error>;
+    fn encode(&self, value: &RegisterValue) -> Result<Vec<u16>, Error>;
+}
 
 pub trait NumericRegisterTrait {
-    fn read(&self, client: &mut modbus::tcp::Transport) -> Result<Vec<f64>, modbus::Error>;
+    fn read(&self, client: &mut dyn RegisterTransport) -> Result<Vec<f64>, Error>;
+    fn decode(&self, words: &[u16]) -> Result<Vec<f64>, Error>;
+    fn encode(&self, value: f64) -> Vec<u16>;
+}
+
+fn expect_words(words: &[u16], count: u16) -> Result<&[u16], Error> {
+    words.get(..count as usize).ok_or(Error::ShortResponse { expected: count as usize, actual: words.len() })
+}
+
+// Unpacks a `read_many` result that must be a number.
+fn expect_numeric(value: Result<RegisterValue, Error>) -> Result<f64, Error> {
+    match value? {
+        RegisterValue::Numeric(value) => Ok(value),
+        RegisterValue::String(value) => Err(Error::InvalidResponse(format!("expected a number, got {:?}", value)))
+    }
+}
+
+fn expect_string(value: Result<RegisterValue, Error>) -> Result<String, Error> {
+    match value? {
+        RegisterValue::String(value) => Ok(value),
+        RegisterValue::Numeric(value) => Err(Error::InvalidResponse(format!("expected a string, got {}", value)))
+    }
 }
 
 pub struct NumericRegister<T> {
@@ -21,10 +101,10 @@ pub struct NumericRegister<T> {
 impl<T> NumericRegister<T> {
     pub fn new(addr: u16, count: u16, gain: u32, unit: &'static str) -> NumericRegister<T> {
         NumericRegister {
-            addr: addr,
-            count: count,
-            gain: gain,
-            unit: unit,
+            addr,
+            count,
+            gain,
+            unit,
             marker: PhantomData
         }
     }
@@ -34,56 +114,195 @@ impl<T> NumericRegister<T> {
     }
 }
 
+impl<T: Sync> Register for NumericRegister<T> where NumericRegister<T>: NumericRegisterTrait {
+    fn addr(&self) -> u16 {
+        self.addr
+    }
+
+    fn count(&self) -> u16 {
+        self.count
+    }
+
+    fn decode(&self, words: &[u16]) -> Result<RegisterValue, Error> {
+        let values = NumericRegisterTrait::decode(self, words)?;
+        match values.first() {
+            Some(&value) => Ok(RegisterValue::Numeric(value)),
+            None => Err(Error::UnsupportedRegister { addr: self.addr, count: self.count })
+        }
+    }
+
+    fn encode(&self, value: &RegisterValue) -> Result<Vec<u16>, Error> {
+        match value {
+            RegisterValue::Numeric(value) => Ok(NumericRegisterTrait::encode(self, *value)),
+            RegisterValue::String(_) => Err(Error::InvalidValue(format!("register {} expects a number", self.addr)))
+        }
+    }
+}
+
 impl NumericRegisterTrait for NumericRegister<u16> {
-    fn read(&self, client: &mut modbus::tcp::Transport) -> Result<Vec<f64>, modbus::Error> {
+    fn read(&self, client: &mut dyn RegisterTransport) -> Result<Vec<f64>, Error> {
         let resp = client.read_holding_registers(self.addr, self.count)?;
+        NumericRegisterTrait::decode(self, &resp)
+    }
+
+    fn decode(&self, words: &[u16]) -> Result<Vec<f64>, Error> {
+        let words = expect_words(words, self.count)?;
         let mut result: Vec<f64> = Vec::new();
-        for elem in resp {
+        for &elem in words {
+            if elem == u16::MAX {
+                return Err(Error::ValueUnavailable { addr: self.addr });
+            }
             result.push((elem as f64) / (self.gain as f64));
         }
         Ok(result)
     }
+
+    fn encode(&self, value: f64) -> Vec<u16> {
+        vec![(value * self.gain as f64).round() as u16]
+    }
 }
 
 impl NumericRegisterTrait for NumericRegister<u32> {
-    fn read(&self, client: &mut modbus::tcp::Transport) -> Result<Vec<f64>, modbus::Error> {
+    fn read(&self, client: &mut dyn RegisterTransport) -> Result<Vec<f64>, Error> {
         let resp = client.read_holding_registers(self.addr, self.count)?;
+        NumericRegisterTrait::decode(self, &resp)
+    }
+
+    fn decode(&self, words: &[u16]) -> Result<Vec<f64>, Error> {
+        let words = expect_words(words, self.count)?;
         let mut bytes: Vec<u8> = Vec::new();
-        for elem in resp {
+        for &elem in words {
             bytes.write_u16::<BigEndian>(elem).unwrap();
         }
         let mut result: Vec<f64> = Vec::new();
         for i in 0..(bytes.len() / 4) {
-            result.push((u32::from_be_bytes([bytes[i * 4], bytes[i * 4 + 1], bytes[i * 4 + 2], bytes[i * 4 + 3]]) as f64) / (self.gain as f64));
+            let value = u32::from_be_bytes([bytes[i * 4], bytes[i * 4 + 1], bytes[i * 4 + 2], bytes[i * 4 + 3]]);
+            if value == u32::MAX {
+                return Err(Error::ValueUnavailable { addr: self.addr });
+            }
+            result.push((value as f64) / (self.gain as f64));
         }
         Ok(result)
     }
+
+    fn encode(&self, value: f64) -> Vec<u16> {
+        let raw = (value * self.gain as f64).round() as u32;
+        vec![(raw >> 16) as u16, raw as u16]
+    }
 }
 
 impl NumericRegisterTrait for NumericRegister<i16> {
-    fn read(&self, client: &mut modbus::tcp::Transport) -> Result<Vec<f64>, modbus::Error> {
+    fn read(&self, client: &mut dyn RegisterTransport) -> Result<Vec<f64>, Error> {
         let resp = client.read_holding_registers(self.addr, self.count)?;
+        NumericRegisterTrait::decode(self, &resp)
+    }
+
+    fn decode(&self, words: &[u16]) -> Result<Vec<f64>, Error> {
+        let words = expect_words(words, self.count)?;
         let mut result: Vec<f64> = Vec::new();
-        for elem in resp {
+        for &elem in words {
+            let elem = elem as i16;
+            if elem == i16::MAX {
+                return Err(Error::ValueUnavailable { addr: self.addr });
+            }
             result.push((elem as f64) / (self.gain as f64));
         }
         Ok(result)
     }
+
+    fn encode(&self, value: f64) -> Vec<u16> {
+        vec![(value * self.gain as f64).round() as i16 as u16]
+    }
 }
 
 impl NumericRegisterTrait for NumericRegister<i32> {
-    fn read(&self, client: &mut modbus::tcp::Transport) -> Result<Vec<f64>, modbus::Error> {
+    fn read(&self, client: &mut dyn RegisterTransport) -> Result<Vec<f64>, Error> {
         let resp = client.read_holding_registers(self.addr, self.count)?;
+        NumericRegisterTrait::decode(self, &resp)
+    }
+
+    fn decode(&self, words: &[u16]) -> Result<Vec<f64>, Error> {
+        let words = expect_words(words, self.count)?;
         let mut bytes: Vec<u8> = Vec::new();
-        for elem in resp {
+        for &elem in words {
             bytes.write_u16::<BigEndian>(elem).unwrap();
         }
         let mut result: Vec<f64> = Vec::new();
         for i in 0..(bytes.len() / 4) {
-            result.push((i32::from_be_bytes([bytes[i * 4], bytes[i * 4 + 1], bytes[i * 4 + 2], bytes[i * 4 + 3]]) as f64) / (self.gain as f64));
+            let value = i32::from_be_bytes([bytes[i * 4], bytes[i * 4 + 1], bytes[i * 4 + 2], bytes[i * 4 + 3]]);
+            if value == i32::MAX {
+                return Err(Error::ValueUnavailable { addr: self.addr });
+            }
+            result.push((value as f64) / (self.gain as f64));
         }
         Ok(result)
     }
+
+    fn encode(&self, value: f64) -> Vec<u16> {
+        let raw = (value * self.gain as f64).round() as i32 as u32;
+        vec![(raw >> 16) as u16, raw as u16]
+    }
+}
+
+// Numeric register that can be written, accepting values within `min..=max` (in the
+// register's unit, before the gain is applied).
+pub struct WritableRegister<T> {
+    register: NumericRegister<T>,
+    min: f64,
+    max: f64
+}
+
+impl<T> WritableRegister<T> {
+    pub fn new(register: NumericRegister<T>, min: f64, max: f64) -> WritableRegister<T> {
+        WritableRegister { register, min, max }
+    }
+
+    pub fn get_unit(&self) -> &'static str {
+        self.register.unit
+    }
+
+    pub fn range(&self) -> (f64, f64) {
+        (self.min, self.max)
+    }
+
+    pub fn check(&self, value: f64) -> Result<(), Error> {
+        if !(self.min..=self.max).contains(&value) {
+            return Err(Error::InvalidValue(format!("{} is outside of {}..={} for register {}", value, self.min, self.max, self.register.addr)));
+        }
+        Ok(())
+    }
+}
+
+impl<T: Sync> Register for WritableRegister<T> where NumericRegister<T>: NumericRegisterTrait {
+    fn addr(&self) -> u16 {
+        self.register.addr
+    }
+
+    fn count(&self) -> u16 {
+        self.register.count
+    }
+
+    fn decode(&self, words: &[u16]) -> Result<RegisterValue, Error> {
+        Register::decode(&self.register, words)
+    }
+
+    fn encode(&self, value: &RegisterValue) -> Result<Vec<u16>, Error> {
+        Register::encode(&self.register, value)
+    }
+}
+
+impl<T> NumericRegisterTrait for WritableRegister<T> where NumericRegister<T>: NumericRegisterTrait {
+    fn read(&self, client: &mut dyn RegisterTransport) -> Result<Vec<f64>, Error> {
+        self.register.read(client)
+    }
+
+    fn decode(&self, words: &[u16]) -> Result<Vec<f64>, Error> {
+        NumericRegisterTrait::decode(&self.register, words)
+    }
+
+    fn encode(&self, value: f64) -> Vec<u16> {
+        NumericRegisterTrait::encode(&self.register, value)
+    }
 }
 
 pub struct StringRegister {
@@ -94,22 +313,111 @@ pub struct StringRegister {
 impl StringRegister {
     pub fn new(addr: u16, count: u16) -> StringRegister {
         StringRegister {
-            addr: addr,
-            count: count
+            addr,
+            count
         }
     }
 
-    pub fn read(&self, client: &mut modbus::tcp::Transport) -> Result<String, modbus::Error> {
+    pub fn read(&self, client: &mut dyn RegisterTransport) -> Result<String, Error> {
         let resp = client.read_holding_registers(self.addr, self.count)?;
+        self.decode(&resp)
+    }
+
+    pub fn decode(&self, words: &[u16]) -> Result<String, Error> {
+        let words = expect_words(words, self.count)?;
         let mut bytes: Vec<u8> = Vec::new();
-        for elem in resp {
+        for &elem in words {
             bytes.write_u16::<BigEndian>(elem).unwrap();
         }
-        Ok(str::from_utf8(&bytes).unwrap().replace(char::from(0), "").to_string())
+        Ok(str::from_utf8(&bytes)?.replace(char::from(0), ""))
+    }
+
+    pub fn encode(&self, value: &str) -> Result<Vec<u16>, Error> {
+        let mut bytes = value.as_bytes().to_vec();
+        if bytes.len() > self.count as usize * 2 {
+            return Err(Error::InvalidValue(format!("\"{}\" does not fit in {} registers", value, self.count)));
+        }
+        bytes.resize(self.count as usize * 2, 0);
+        Ok(bytes.chunks(2).map(|b| u16::from_be_bytes([b[0], b[1]])).collect())
+    }
+}
+
+impl Register for StringRegister {
+    fn addr(&self) -> u16 {
+        self.addr
+    }
+
+    fn count(&self) -> u16 {
+        self.count
+    }
+
+    fn decode(&self, words: &[u16]) -> Result<RegisterValue, Error> {
+        Ok(RegisterValue::String(StringRegister::decode(self, words)?))
+    }
+
+    fn encode(&self, value: &RegisterValue) -> Result<Vec<u16>, Error> {
+        match value {
+            RegisterValue::String(value) => StringRegister::encode(self, value),
+            RegisterValue::Numeric(_) => Err(Error::InvalidValue(format!("register {} expects a string", self.addr)))
+        }
     }
 }
 
-pub const DEVICE_STATUS_DEFINITIONS: [(u16, &'static str); 30] = [
+// Register where every bit is a flag, e.g. the alarm registers. Up to two words are combined
+// into one big-endian value.
+pub struct BitfieldRegister {
+    addr: u16,
+    count: u16
+}
+
+impl BitfieldRegister {
+    pub fn new(addr: u16, count: u16) -> BitfieldRegister {
+        BitfieldRegister {
+            addr,
+            count
+        }
+    }
+
+    pub fn read(&self, client: &mut dyn RegisterTransport) -> Result<u32, Error> {
+        let resp = client.read_holding_registers(self.addr, self.count)?;
+        self.decode(&resp)
+    }
+
+    pub fn decode(&self, words: &[u16]) -> Result<u32, Error> {
+        if self.count > 2 {
+            return Err(Error::UnsupportedRegister { addr: self.addr, count: self.count });
+        }
+        let words = expect_words(words, self.count)?;
+        Ok(words.iter().fold(0, |bits, &word| (bits << 16) | word as u32))
+    }
+
+    pub fn encode(&self, bits: u32) -> Vec<u16> {
+        (0..self.count).rev().map(|i| (bits >> (16 * i as u32)) as u16).collect()
+    }
+}
+
+impl Register for BitfieldRegister {
+    fn addr(&self) -> u16 {
+        self.addr
+    }
+
+    fn count(&self) -> u16 {
+        self.count
+    }
+
+    fn decode(&self, words: &[u16]) -> Result<RegisterValue, Error> {
+        Ok(RegisterValue::Numeric(BitfieldRegister::decode(self, words)? as f64))
+    }
+
+    fn encode(&self, value: &RegisterValue) -> Result<Vec<u16>, Error> {
+        match value {
+            RegisterValue::Numeric(value) => Ok(BitfieldRegister::encode(self, *value as u32)),
+            RegisterValue::String(_) => Err(Error::InvalidValue(format!("register {} expects a number", self.addr)))
+        }
+    }
+}
+
+pub const DEVICE_STATUS_DEFINITIONS: [(u16, &str); 30] = [
     (0x0000, "Standby, initializing"),
     (0x0001, "Standby, detecting insulation resistance"),
     (0x0002, "Standby, detecting irradiation"),
@@ -142,42 +450,241 @@ pub const DEVICE_STATUS_DEFINITIONS: [(u16, &'static str); 30] = [
     (0xA000, "Standby, no irradiation"),
 ];
 
-pub struct HuaweiSolar {
-    pub client: modbus::tcp::Transport
+pub struct HuaweiSolar<C: RegisterTransport = TcpTransport> {
+    pub client: C,
+    options: ConnectionOptions,
+    last_request: Option<time::Instant>,
+    connector: Option<Connector<C>>,
+    broken: bool,
+    stats: ConnectionStats,
+    listener: Option<Listener>
 }
 
+type Connector<C> = Box<dyn FnMut() -> Result<C, Error> + Send>;
+type Listener = Box<dyn FnMut(&ConnectionEvent) + Send>;
+
 impl HuaweiSolar {
-    #[allow(unused_mut)]
-    pub fn new_connection(ip: &str, port: u16, slave_id: u8) -> Result<HuaweiSolar, modbus::Error> {
-        let mut cfg = tcp::Config::default();
-        cfg.modbus_uid = slave_id;
-        cfg.tcp_port = port;
-        let mut client = tcp::Transport::new_with_cfg(ip, cfg)?;
-        thread::sleep(time::Duration::from_millis(1000));
-        Ok(HuaweiSolar { client: client })
+    pub fn new_connection(ip: &str, port: u16, slave_id: u8) -> Result<HuaweiSolar, Error> {
+        HuaweiSolar::new_connection_with_options(ip, port, slave_id, ConnectionOptions::default())
+    }
+
+    pub fn new_connection_with_options(ip: &str, port: u16, slave_id: u8, options: ConnectionOptions) -> Result<HuaweiSolar, Error> {
+        let (ip, connect_options) = (ip.to_string(), options.clone());
+        let connector = move || TcpTransport::connect(&ip, port, slave_id, &connect_options);
+        let client = connector()?;
+        Ok(HuaweiSolar::with_options(client, options).reconnect_with(connector))
+    }
+}
+
+// For transparent serial-to-Ethernet converters, which pass RTU frames through a raw TCP socket.
+impl HuaweiSolar<RtuTransport<TcpStream>> {
+    pub fn new_rtu_over_tcp_connection(ip: &str, port: u16, slave_id: u8) -> Result<HuaweiSolar<RtuTransport<TcpStream>>, Error> {
+        HuaweiSolar::new_rtu_over_tcp_connection_with_options(ip, port, slave_id, ConnectionOptions::default())
+    }
+
+    pub fn new_rtu_over_tcp_connection_with_options(ip: &str, port: u16, slave_id: u8, options: ConnectionOptions) -> Result<HuaweiSolar<RtuTransport<TcpStream>>, Error> {
+        let (ip, connect_options) = (ip.to_string(), options.clone());
+        let connector = move || Ok(RtuTransport::new(tcp::open_stream(&ip, port, &connect_options)?, slave_id));
+        let client = connector()?;
+        Ok(HuaweiSolar::with_options(client, options).reconnect_with(connector))
+    }
+}
+
+#[cfg(feature = "serial")]
+impl HuaweiSolar<RtuTransport<Box<dyn serialport::SerialPort>>> {
+    pub fn new_rtu_connection(path: &str, slave_id: u8, config: &SerialConfig) -> Result<HuaweiSolar<RtuTransport<Box<dyn serialport::SerialPort>>>, Error> {
+        let (path, config) = (path.to_string(), config.clone());
+        let connector = move || RtuTransport::open(&path, slave_id, &config);
+        let client = connector()?;
+        Ok(HuaweiSolar::new(client).reconnect_with(connector))
+    }
+}
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    // Socket related options (timeouts, keepalive, settle delay) only apply when connecting,
+    // so with `new` and `with_options` only request pacing and retries are used.
+    pub fn new(client: C) -> HuaweiSolar<C> {
+        HuaweiSolar::with_options(client, ConnectionOptions::default())
+    }
+
+    pub fn with_options(client: C, options: ConnectionOptions) -> HuaweiSolar<C> {
+        HuaweiSolar { client, options, last_request: None, connector: None, broken: false, stats: ConnectionStats::default(), listener: None }
+    }
+
+    // Makes the client reopen its connection with `connector` after an I/O error or timeout.
+    // The `new_*connection*` constructors set this up already.
+    pub fn reconnect_with<F: FnMut() -> Result<C, Error> + Send + 'static>(mut self, connector: F) -> HuaweiSolar<C> {
+        self.connector = Some(Box::new(connector));
+        self
     }
 
-    pub fn read_numeric_register<T: NumericRegisterTrait>(&mut self, reg: &T) -> Result<f64, modbus::Error> {
-        match reg.read(&mut self.client) {
-            Ok(v) => Ok(v[0]),
-            Err(e) => Err(e)
+    // Called on lost connections, reconnects and retries, e.g. for logging.
+    pub fn on_connection_event<F: FnMut(&ConnectionEvent) + Send + 'static>(&mut self, listener: F) {
+        self.listener = Some(Box::new(listener));
+    }
+
+    pub fn stats(&self) -> &ConnectionStats {
+        &self.stats
+    }
+
+    // Replaces the client with a freshly connected one.
+    pub fn reconnect(&mut self) -> Result<(), Error> {
+        let connector = match self.connector.as_mut() {
+            Some(connector) => connector,
+            None => return Err(Error::Connection(std::io::Error::new(std::io::ErrorKind::Unsupported, "no way to reconnect this client")))
+        };
+        match connector() {
+            Ok(client) => {
+                self.client = client;
+                self.broken = false;
+                self.stats.reconnects += 1;
+                self.notify(&ConnectionEvent::Reconnected);
+                Ok(())
+            },
+            Err(e) => {
+                self.stats.failed_reconnects += 1;
+                self.notify(&ConnectionEvent::ReconnectFailed(&e));
+                Err(e)
+            }
         }
     }
 
-    pub fn read_string_register(&mut self, reg: &StringRegister) -> Result<String, modbus::Error> {
-        reg.read(&mut self.client)
+    pub fn read_numeric_register<T: Register + NumericRegisterTrait>(&mut self, reg: &T) -> Result<f64, Error> {
+        let words = self.read_words(reg.addr(), reg.count())?;
+        let values = NumericRegisterTrait::decode(reg, &words)?;
+        values.first().copied().ok_or(Error::ShortResponse { expected: 1, actual: 0 })
     }
 
-    pub fn read_device_status(&mut self, reg: &NumericRegister<u16>) -> Result<String, modbus::Error> {
+    pub fn read_string_register(&mut self, reg: &StringRegister) -> Result<String, Error> {
+        let words = self.read_words(reg.addr, reg.count)?;
+        reg.decode(&words)
+    }
+
+    // Writes `value` with 0x06 or 0x10 depending on the register size, then reads the register
+    // back to make sure the inverter took the value.
+    pub fn write_register<T: Sync>(&mut self, reg: &WritableRegister<T>, value: f64) -> Result<(), Error> where NumericRegister<T>: NumericRegisterTrait {
+        reg.check(value)?;
+        let words = NumericRegisterTrait::encode(reg, value);
+        self.write_words(reg.addr(), &words)?;
+        let read = self.read_words(reg.addr(), reg.count())?;
+        if expect_words(&read, reg.count())? != &words[..] {
+            let read = NumericRegisterTrait::decode(reg, &read)?;
+            return Err(Error::WriteNotApplied { addr: reg.addr(), written: value, read: read[0] });
+        }
+        Ok(())
+    }
+
+    pub fn read_device_status(&mut self, reg: &NumericRegister<u16>) -> Result<DeviceStatus, Error> {
         let status = self.read_numeric_register(reg)?;
-        let mut result = String::new();
-        for (code, desc) in DEVICE_STATUS_DEFINITIONS.iter() {
-            if status as u16 == *code {
-                result = desc.to_string();
+        Ok(DeviceStatus::from_code(status as u16))
+    }
+
+    // Reads all given registers using as few Modbus requests as possible. Results are returned
+    // in the same order as `regs`. If a batched request fails, its registers are re-read one by
+    // one, so a single unsupported address does not take down its neighbours.
+    pub fn read_many(&mut self, regs: &[&dyn Register]) -> Vec<Result<RegisterValue, Error>> {
+        let mut batch = batch::BatchRead::new(regs);
+        for block in batch.blocks() {
+            let words = self.read_words(block.addr, block.count);
+            for i in batch.complete(&block, words) {
+                batch.set(i, self.read_register(regs[i]));
             }
         }
-        Ok(result)
+        batch.finish()
+    }
+
+    fn read_register(&mut self, reg: &dyn Register) -> Result<RegisterValue, Error> {
+        let words = self.read_words(reg.addr(), reg.count())?;
+        reg.decode(&words)
+    }
+
+    fn read_words(&mut self, addr: u16, count: u16) -> Result<Vec<u16>, Error> {
+        self.request(|client| client.read_holding_registers(addr, count))
     }
+
+    // Writing an absolute value is idempotent, so settings share the retry handling of reads.
+    // Commands that trigger an action use `send_command` instead. Multi-register values must
+    // be written in one request.
+    fn write_words(&mut self, addr: u16, values: &[u16]) -> Result<(), Error> {
+        self.request(|client| match values {
+            [value] => client.write_single_register(addr, *value),
+            _ => client.write_multiple_registers(addr, values)
+        })
+    }
+
+    // Commands trigger an action on the inverter, so they are sent only once. Whether they took
+    // effect has to be checked by reading the state they change.
+    fn send_command(&mut self, addr: u16, value: u16) -> Result<(), Error> {
+        self.attempt(&mut |client: &mut C| client.write_single_register(addr, value))
+    }
+
+    // All reads and setting writes go through here to apply request pacing, reconnects and
+    // retries. Only pass idempotent requests, since they may be repeated after a lost connection.
+    fn request<T, F: FnMut(&mut C) -> Result<T, Error>>(&mut self, mut request: F) -> Result<T, Error> {
+        let mut attempt = 0;
+        loop {
+            match self.attempt(&mut request) {
+                Err(e) => {
+                    if !e.is_transient() || attempt >= self.options.retries {
+                        return Err(e);
+                    }
+                    attempt += 1;
+                    self.stats.retries += 1;
+                    self.notify(&ConnectionEvent::Retry { attempt, error: &e });
+                    thread::sleep(self.options.backoff(attempt - 1));
+                },
+                result => return result
+            }
+        }
+    }
+
+    fn attempt<T, F: FnMut(&mut C) -> Result<T, Error>>(&mut self, request: &mut F) -> Result<T, Error> {
+        let result = self.ensure_connected().and_then(|_| {
+            self.pace();
+            self.stats.requests += 1;
+            request(&mut self.client)
+        });
+        if let Err(e) = &result {
+            self.stats.failures += 1;
+            if e.breaks_connection() && !self.broken && self.can_reconnect() {
+                self.broken = true;
+                self.notify(&ConnectionEvent::Lost(e));
+            }
+        }
+        result
+    }
+
+    fn can_reconnect(&self) -> bool {
+        self.options.auto_reconnect && self.connector.is_some()
+    }
+
+    fn ensure_connected(&mut self) -> Result<(), Error> {
+        if self.broken {
+            self.reconnect()?;
+        }
+        Ok(())
+    }
+
+    fn notify(&mut self, event: &ConnectionEvent) {
+        if let Some(listener) = self.listener.as_mut() {
+            listener(event);
+        }
+    }
+
+    fn pace(&mut self) {
+        if let Some(last_request) = self.last_request {
+            let elapsed = last_request.elapsed();
+            if elapsed < self.options.request_delay {
+                thread::sleep(self.options.request_delay - elapsed);
+            }
+        }
+        self.last_request = Some(time::Instant::now());
+    }
+}
+
+// Returns an empty string for unknown codes, see `DeviceStatus` for a typed alternative.
+pub fn describe_device_status(status: u16) -> String {
+    DeviceStatus::from_code(status).description().unwrap_or("").to_string()
 }
 
 
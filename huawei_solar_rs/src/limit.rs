@@ -0,0 +1,114 @@
+use crate::{expect_numeric, registers, Error, HuaweiSolar, Register, RegisterTransport};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PowerLimit {
+    Percent(f64),
+    Watts(f64)
+}
+
+// Both derating settings, and the output power they allow together.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActivePowerLimit {
+    pub percent: f64,
+    pub watts: f64,
+    pub effective_watts: f64
+}
+
+// Limit on the power fed into the grid, as measured by the power meter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExportLimit {
+    Unlimited,
+    // Controlled by the dry contacts of a ripple control receiver.
+    DiScheduling,
+    Zero,
+    Watts(f64),
+    Percent(f64),
+    Unknown(u16)
+}
+
+impl fmt::Display for PowerLimit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PowerLimit::Percent(percent) => write!(f, "{} %", percent),
+            PowerLimit::Watts(watts) => write!(f, "{} W", watts)
+        }
+    }
+}
+
+impl fmt::Display for ExportLimit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExportLimit::Unlimited => write!(f, "unlimited"),
+            ExportLimit::DiScheduling => write!(f, "DI active scheduling"),
+            ExportLimit::Zero => write!(f, "zero export"),
+            ExportLimit::Watts(watts) => write!(f, "{} W", watts),
+            ExportLimit::Percent(percent) => write!(f, "{} %", percent),
+            ExportLimit::Unknown(code) => write!(f, "unknown mode {}", code)
+        }
+    }
+}
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    pub fn read_active_power_limit(&mut self) -> Result<ActivePowerLimit, Error> {
+        let regs: [&dyn Register; 3] = [
+            &registers::ACTIVE_POWER_PERCENTAGE_DERATING,
+            &registers::ACTIVE_POWER_FIXED_DERATING,
+            &registers::MAX_ACTIVE_POWER
+        ];
+        let mut values = self.read_many(&regs).into_iter().map(expect_numeric);
+        let mut next = || values.next().unwrap();
+        let (percent, watts, max_active_power) = (next()?, next()?, next()?);
+        let effective_watts = (max_active_power * percent / 100.0).min(watts).min(max_active_power);
+        Ok(ActivePowerLimit { percent, watts, effective_watts })
+    }
+
+    // Only changes the given kind of derating, as the other one keeps applying.
+    pub fn set_active_power_limit(&mut self, limit: PowerLimit) -> Result<(), Error> {
+        match limit {
+            PowerLimit::Percent(percent) => self.write_register(&registers::ACTIVE_POWER_PERCENTAGE_DERATING, percent),
+            PowerLimit::Watts(watts) => self.write_register(&registers::ACTIVE_POWER_FIXED_DERATING, watts)
+        }
+    }
+
+    pub fn clear_active_power_limit(&mut self) -> Result<(), Error> {
+        let max_active_power = self.read_numeric_register(&registers::MAX_ACTIVE_POWER)?;
+        self.set_active_power_limit(PowerLimit::Percent(100.0))?;
+        self.set_active_power_limit(PowerLimit::Watts(max_active_power))
+    }
+
+    pub fn read_export_limit(&mut self) -> Result<ExportLimit, Error> {
+        let mode = self.read_numeric_register(&registers::ACTIVE_POWER_CONTROL_MODE)? as u16;
+        Ok(match mode {
+            0 => ExportLimit::Unlimited,
+            1 => ExportLimit::DiScheduling,
+            5 => ExportLimit::Zero,
+            6 => ExportLimit::Watts(self.read_numeric_register(&registers::MAXIMUM_FEED_GRID_POWER)?),
+            7 => ExportLimit::Percent(self.read_numeric_register(&registers::MAXIMUM_FEED_GRID_POWER_PERCENT)?),
+            _ => ExportLimit::Unknown(mode)
+        })
+    }
+
+    // The limit value is written before switching the mode, so the inverter never applies a
+    // stale one.
+    pub fn set_export_limit(&mut self, limit: ExportLimit) -> Result<(), Error> {
+        let mode = match limit {
+            ExportLimit::Unlimited => 0,
+            ExportLimit::DiScheduling => 1,
+            ExportLimit::Zero => 5,
+            ExportLimit::Watts(watts) => {
+                self.write_register(&registers::MAXIMUM_FEED_GRID_POWER, watts)?;
+                6
+            },
+            ExportLimit::Percent(percent) => {
+                self.write_register(&registers::MAXIMUM_FEED_GRID_POWER_PERCENT, percent)?;
+                7
+            },
+            ExportLimit::Unknown(mode) => mode
+        };
+        self.write_register(&registers::ACTIVE_POWER_CONTROL_MODE, mode as f64)
+    }
+}
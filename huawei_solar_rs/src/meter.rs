@@ -0,0 +1,119 @@
+use crate::{expect_numeric, registers, Error, HuaweiSolar, Register, RegisterTransport};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MeterType {
+    SinglePhase,
+    ThreePhase,
+    Unknown(u16)
+}
+
+impl MeterType {
+    pub fn from_code(code: u16) -> MeterType {
+        match code {
+            0 => MeterType::SinglePhase,
+            1 => MeterType::ThreePhase,
+            _ => MeterType::Unknown(code)
+        }
+    }
+}
+
+impl fmt::Display for MeterType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MeterType::SinglePhase => write!(f, "single-phase"),
+            MeterType::ThreePhase => write!(f, "three-phase"),
+            MeterType::Unknown(code) => write!(f, "unknown type {}", code)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeterPhase {
+    pub voltage: f64,
+    pub current: f64,
+    pub active_power: f64
+}
+
+// Power is positive when feeding into the grid and negative when drawing from it.
+// `phases` holds one entry for single-phase meters and three (A, B, C) otherwise, and
+// `line_voltages` (A-B, B-C, C-A) is empty for single-phase meters.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerMeter {
+    pub online: bool,
+    pub meter_type: MeterType,
+    pub phases: Vec<MeterPhase>,
+    pub line_voltages: Vec<f64>,
+    pub active_power: f64,
+    pub reactive_power: f64,
+    pub power_factor: f64,
+    pub frequency: f64,
+    pub exported_energy: f64,
+    pub imported_energy: f64,
+    pub reactive_energy: f64
+}
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    pub fn read_power_meter(&mut self) -> Result<PowerMeter, Error> {
+        let regs: [&dyn Register; 21] = [
+            &registers::METER_STATUS,
+            &registers::METER_TYPE,
+            &registers::METER_ACTIVE_POWER,
+            &registers::METER_REACTIVE_POWER,
+            &registers::METER_POWER_FACTOR,
+            &registers::METER_FREQUENCY,
+            &registers::METER_EXPORTED_ENERGY,
+            &registers::METER_IMPORTED_ENERGY,
+            &registers::METER_REACTIVE_ENERGY,
+            &registers::METER_PHASE_A_VOLTAGE,
+            &registers::METER_PHASE_A_CURRENT,
+            &registers::METER_PHASE_B_VOLTAGE,
+            &registers::METER_PHASE_B_CURRENT,
+            &registers::METER_PHASE_C_VOLTAGE,
+            &registers::METER_PHASE_C_CURRENT,
+            &registers::METER_PHASE_A_ACTIVE_POWER,
+            &registers::METER_PHASE_B_ACTIVE_POWER,
+            &registers::METER_PHASE_C_ACTIVE_POWER,
+            &registers::METER_LINE_VOLTAGE_A_B,
+            &registers::METER_LINE_VOLTAGE_B_C,
+            &registers::METER_LINE_VOLTAGE_C_A
+        ];
+        let mut values = self.read_many(&regs).into_iter().map(expect_numeric);
+        let mut next = || values.next().unwrap();
+        let mut meter = PowerMeter {
+            online: next()? == 1.0,
+            meter_type: MeterType::from_code(next()? as u16),
+            phases: Vec::new(),
+            line_voltages: Vec::new(),
+            active_power: next()?,
+            reactive_power: next()?,
+            power_factor: next()?,
+            frequency: next()?,
+            exported_energy: next()?,
+            imported_energy: next()?,
+            reactive_energy: next()?
+        };
+
+        // Registers of the phases a single-phase meter lacks usually hold the "unavailable"
+        // sentinel, so they are only decoded for three-phase meters.
+        if meter.meter_type == MeterType::SinglePhase {
+            let (voltage, current) = (next()?, next()?);
+            meter.phases.push(MeterPhase { voltage, current, active_power: meter.active_power });
+        } else {
+            let mut phases = Vec::new();
+            for _ in 0..3 {
+                phases.push((next()?, next()?));
+            }
+            for (voltage, current) in phases {
+                meter.phases.push(MeterPhase { voltage, current, active_power: next()? });
+            }
+            for _ in 0..3 {
+                meter.line_voltages.push(next()?);
+            }
+        }
+        Ok(meter)
+    }
+}
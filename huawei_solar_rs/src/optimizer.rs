@@ -0,0 +1,95 @@
+use crate::{expect_numeric, registers, Error, HuaweiSolar, Register, RegisterTransport};
+
+const REAL_TIME_DATA_FILE: u8 = 0x44;
+const READING_SIZE: usize = 26;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptimizerSummary {
+    pub total: u16,
+    pub online: u16,
+    pub feature_data: u16
+}
+
+// One sample of a single optimizer. `time` is the epoch second the inverter took the
+// sample at, and `position` the optimizer's logical address on the string.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptimizerReading {
+    pub time: u32,
+    pub position: u16,
+    pub running_status: u16,
+    pub alarm: u32,
+    pub output_power: f64,
+    pub output_voltage: f64,
+    pub output_current: f64,
+    pub input_voltage: f64,
+    pub input_current: f64,
+    pub voltage_to_ground: f64,
+    pub temperature: f64,
+    pub total_energy: f64
+}
+
+impl OptimizerSummary {
+    pub fn offline(&self) -> u16 {
+        self.total.saturating_sub(self.online)
+    }
+}
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    pub fn read_optimizer_summary(&mut self) -> Result<OptimizerSummary, Error> {
+        let regs: [&dyn Register; 3] = [
+            &registers::NUMBER_OF_OPTIMIZERS,
+            &registers::NUMBER_OF_ONLINE_OPTIMIZERS,
+            &registers::OPTIMIZER_FEATURE_DATA
+        ];
+        let mut values = self.read_many(&regs).into_iter().map(expect_numeric);
+        let mut next = || values.next().unwrap().map(|value| value as u16);
+        Ok(OptimizerSummary { total: next()?, online: next()?, feature_data: next()? })
+    }
+
+    pub fn read_optimizer_data(&mut self) -> Result<Vec<OptimizerReading>, Error> {
+        parse_optimizer_data(&self.read_file(REAL_TIME_DATA_FILE, &[])?)
+    }
+}
+
+// The real-time data file starts with a u32 file version, followed by data units of a u32
+// timestamp, a u16 optimizer count and that many 26-byte readings:
+//
+//   position u16, output power u16 (0.1 W), voltage to ground i16 (0.1 V), alarm u32,
+//   output voltage u16 (0.1 V), output current u16 (0.01 A), input voltage u16 (0.1 V),
+//   input current u16 (0.01 A), temperature i16 (0.1 °C), running status u16,
+//   total energy u32 (Wh, returned in kWh)
+//
+// All fields are big-endian.
+pub fn parse_optimizer_data(data: &[u8]) -> Result<Vec<OptimizerReading>, Error> {
+    let truncated = || Error::InvalidResponse("optimizer data file is truncated".to_string());
+    let mut rest = data.get(4..).ok_or_else(truncated)?;
+    let mut readings = Vec::new();
+    while !rest.is_empty() {
+        let header = rest.get(..6).ok_or_else(truncated)?;
+        let time = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        let count = u16::from_be_bytes([header[4], header[5]]) as usize;
+        let units = rest.get(6..6 + count * READING_SIZE).ok_or_else(truncated)?;
+        for unit in units.chunks(READING_SIZE) {
+            let u16_at = |i: usize| u16::from_be_bytes([unit[i], unit[i + 1]]);
+            let u32_at = |i: usize| u32::from_be_bytes([unit[i], unit[i + 1], unit[i + 2], unit[i + 3]]);
+            readings.push(OptimizerReading {
+                time,
+                position: u16_at(0),
+                output_power: u16_at(2) as f64 / 10.0,
+                voltage_to_ground: u16_at(4) as i16 as f64 / 10.0,
+                alarm: u32_at(6),
+                output_voltage: u16_at(10) as f64 / 10.0,
+                output_current: u16_at(12) as f64 / 100.0,
+                input_voltage: u16_at(14) as f64 / 10.0,
+                input_current: u16_at(16) as f64 / 100.0,
+                temperature: u16_at(18) as i16 as f64 / 10.0,
+                running_status: u16_at(20),
+                total_energy: u32_at(22) as f64 / 1000.0
+            });
+        }
+        rest = &rest[6 + count * READING_SIZE..];
+    }
+    Ok(readings)
+}
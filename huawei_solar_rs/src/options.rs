@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct ConnectionOptions {
+    pub(crate) settle_delay: Duration,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) read_timeout: Option<Duration>,
+    pub(crate) write_timeout: Option<Duration>,
+    pub(crate) request_delay: Duration,
+    pub(crate) retries: u32,
+    pub(crate) retry_backoff: Duration,
+    pub(crate) keepalive: Option<Duration>,
+    pub(crate) auto_reconnect: bool
+}
+
+// Defaults match the historical behaviour of `HuaweiSolar::new_connection`: wait one second
+// after connecting and never time out. A read failing on a dropped connection is repeated once
+// on a new connection, so callers do not see the drop.
+impl Default for ConnectionOptions {
+    fn default() -> ConnectionOptions {
+        ConnectionOptions {
+            settle_delay: Duration::from_millis(1000),
+            connect_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+            request_delay: Duration::ZERO,
+            retries: 1,
+            retry_backoff: Duration::from_millis(500),
+            keepalive: None,
+            auto_reconnect: true
+        }
+    }
+}
+
+impl ConnectionOptions {
+    pub fn new() -> ConnectionOptions {
+        ConnectionOptions::default()
+    }
+
+    // Direct connection to the inverter's own WLAN access point.
+    pub fn inverter_wlan() -> ConnectionOptions {
+        ConnectionOptions::new()
+            .connect_timeout(Duration::from_secs(5))
+            .read_timeout(Duration::from_secs(5))
+            .write_timeout(Duration::from_secs(5))
+            .retries(2)
+    }
+
+    // The S-Dongle is slow to answer and drops requests sent back to back.
+    pub fn s_dongle() -> ConnectionOptions {
+        ConnectionOptions::new()
+            .settle_delay(Duration::from_secs(2))
+            .connect_timeout(Duration::from_secs(5))
+            .read_timeout(Duration::from_secs(10))
+            .write_timeout(Duration::from_secs(5))
+            .request_delay(Duration::from_millis(100))
+            .retries(3)
+            .retry_backoff(Duration::from_secs(1))
+            .keepalive(Duration::from_secs(60))
+    }
+
+    // The SmartLogger answers quickly and needs no settle time.
+    pub fn smartlogger() -> ConnectionOptions {
+        ConnectionOptions::new()
+            .settle_delay(Duration::ZERO)
+            .connect_timeout(Duration::from_secs(5))
+            .read_timeout(Duration::from_secs(3))
+            .write_timeout(Duration::from_secs(3))
+            .retries(2)
+            .keepalive(Duration::from_secs(60))
+    }
+
+    // Time to wait after connecting before the first request.
+    pub fn settle_delay(mut self, delay: Duration) -> ConnectionOptions {
+        self.settle_delay = delay;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> ConnectionOptions {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn read_timeout(mut self, timeout: Duration) -> ConnectionOptions {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    pub fn write_timeout(mut self, timeout: Duration) -> ConnectionOptions {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    // Minimum time between the start of two consecutive requests.
+    pub fn request_delay(mut self, delay: Duration) -> ConnectionOptions {
+        self.request_delay = delay;
+        self
+    }
+
+    // How many times a request failing with a transient error is repeated.
+    pub fn retries(mut self, retries: u32) -> ConnectionOptions {
+        self.retries = retries;
+        self
+    }
+
+    // Delay before the first retry. It doubles with every further attempt.
+    pub fn retry_backoff(mut self, backoff: Duration) -> ConnectionOptions {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    // Idle time after which TCP keepalive probes are sent.
+    pub fn keepalive(mut self, idle: Duration) -> ConnectionOptions {
+        self.keepalive = Some(idle);
+        self
+    }
+
+    // Whether a connection lost to an I/O error or timeout is reopened before the next request.
+    // Only has an effect when the client knows how to reconnect, see `HuaweiSolar::reconnect_with`.
+    pub fn auto_reconnect(mut self, enabled: bool) -> ConnectionOptions {
+        self.auto_reconnect = enabled;
+        self
+    }
+
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        self.retry_backoff.saturating_mul(1 << attempt.min(16))
+    }
+}
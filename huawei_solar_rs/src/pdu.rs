@@ -0,0 +1,119 @@
+use crate::{Error, ExceptionCode};
+
+pub const READ_HOLDING_REGISTERS: u8 = 0x03;
+pub const WRITE_SINGLE_REGISTER: u8 = 0x06;
+pub const WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+// Huawei's private function, used among others for file uploads.
+pub const HUAWEI_FILE: u8 = 0x41;
+pub const MBAP_HEADER_SIZE: usize = 7;
+
+pub fn read_holding_registers_request(addr: u16, count: u16) -> Vec<u8> {
+    let mut pdu = vec![READ_HOLDING_REGISTERS];
+    pdu.extend_from_slice(&addr.to_be_bytes());
+    pdu.extend_from_slice(&count.to_be_bytes());
+    pdu
+}
+
+pub fn write_single_register_request(addr: u16, value: u16) -> Vec<u8> {
+    let mut pdu = vec![WRITE_SINGLE_REGISTER];
+    pdu.extend_from_slice(&addr.to_be_bytes());
+    pdu.extend_from_slice(&value.to_be_bytes());
+    pdu
+}
+
+pub fn write_multiple_registers_request(addr: u16, values: &[u16]) -> Vec<u8> {
+    let mut pdu = vec![WRITE_MULTIPLE_REGISTERS];
+    pdu.extend_from_slice(&addr.to_be_bytes());
+    pdu.extend_from_slice(&(values.len() as u16).to_be_bytes());
+    pdu.push((values.len() * 2) as u8);
+    for value in values {
+        pdu.extend_from_slice(&value.to_be_bytes());
+    }
+    pdu
+}
+
+// Checks the function code of a response PDU and turns exception responses into errors.
+pub fn check_response(function: u8, pdu: &[u8]) -> Result<(), Error> {
+    match pdu.first() {
+        Some(&code) if code == function => Ok(()),
+        Some(&code) if code == function | 0x80 => match pdu.get(1) {
+            Some(&exception) => Err(Error::Exception(ExceptionCode::from_code(exception))),
+            None => Err(Error::InvalidResponse("truncated exception response".to_string()))
+        },
+        Some(&code) => Err(Error::InvalidResponse(format!("unexpected function code 0x{:02X}", code))),
+        None => Err(Error::InvalidResponse("empty response".to_string()))
+    }
+}
+
+pub fn parse_read_holding_registers_response(pdu: &[u8], count: u16) -> Result<Vec<u16>, Error> {
+    check_response(READ_HOLDING_REGISTERS, pdu)?;
+    let byte_count = *pdu.get(1).ok_or(Error::ShortResponse { expected: count as usize, actual: 0 })? as usize;
+    let data = &pdu[2..];
+    if byte_count != data.len() || byte_count & 1 != 0 {
+        return Err(Error::InvalidResponse(format!("byte count {} does not match {} data bytes", byte_count, data.len())));
+    }
+    if byte_count < count as usize * 2 {
+        return Err(Error::ShortResponse { expected: count as usize, actual: byte_count / 2 });
+    }
+    Ok(data.chunks(2).map(|w| u16::from_be_bytes([w[0], w[1]])).collect())
+}
+
+// Write responses echo the start address (and the value or register count) of the request.
+pub fn check_write_response(request: &[u8], response: &[u8]) -> Result<(), Error> {
+    check_response(request[0], response)?;
+    if response.len() != 5 || response[1..5] != request[1..5] {
+        return Err(Error::InvalidResponse("write response does not match request".to_string()));
+    }
+    Ok(())
+}
+
+pub fn huawei_file_request(sub_function: u8, content: &[u8]) -> Vec<u8> {
+    let mut pdu = vec![HUAWEI_FILE, sub_function, content.len() as u8];
+    pdu.extend_from_slice(content);
+    pdu
+}
+
+// Returns the content of a response to `huawei_file_request`.
+pub fn parse_huawei_file_response(sub_function: u8, pdu: &[u8]) -> Result<&[u8], Error> {
+    check_response(HUAWEI_FILE, pdu)?;
+    if pdu.len() < 3 || pdu[1] != sub_function {
+        return Err(Error::InvalidResponse(format!("unexpected response to file sub-function 0x{:02X}", sub_function)));
+    }
+    pdu.get(3..3 + pdu[2] as usize).ok_or_else(|| Error::InvalidResponse("file response is shorter than its length byte".to_string()))
+}
+
+pub fn mbap_header(tid: u16, uid: u8, pdu_len: usize) -> [u8; MBAP_HEADER_SIZE] {
+    let tid = tid.to_be_bytes();
+    let len = (pdu_len as u16 + 1).to_be_bytes();
+    [tid[0], tid[1], 0, 0, len[0], len[1], uid]
+}
+
+// Returns the transaction id and the number of PDU bytes following the header.
+pub fn parse_mbap_header(header: &[u8; MBAP_HEADER_SIZE]) -> Result<(u16, usize), Error> {
+    let tid = u16::from_be_bytes([header[0], header[1]]);
+    let protocol = u16::from_be_bytes([header[2], header[3]]);
+    let len = u16::from_be_bytes([header[4], header[5]]) as usize;
+    if protocol != 0 {
+        return Err(Error::InvalidResponse(format!("unknown protocol id {}", protocol)));
+    }
+    if len < 2 {
+        return Err(Error::InvalidResponse(format!("invalid MBAP length {}", len)));
+    }
+    Ok((tid, len - 1))
+}
+
+// CRC-16/MODBUS used by RTU framing. It is transmitted low byte first.
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &byte in data {
+        crc ^= byte as u16;
+        for _ in 0..8 {
+            if crc & 1 != 0 {
+                crc = (crc >> 1) ^ 0xA001;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+    crc
+}
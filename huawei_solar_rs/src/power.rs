@@ -0,0 +1,57 @@
+use crate::{registers, DeviceStatus, Error, HuaweiSolar, Register, RegisterTransport};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Standby states a started inverter settles in when there is not enough sunlight to feed in.
+const STARTED_STANDBY: [u16; 2] = [0x0002, 0xA000];
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    // Starts the inverter and waits until it is on-grid, or in standby waiting for sunlight. Standby
+    // only counts once the status has changed after the command, so an inverter that was already
+    // waiting and ignored it is not taken as started. A fault fails right away. The command is not
+    // repeated on errors, only the status poll is.
+    pub fn power_on(&mut self, timeout: Duration) -> Result<DeviceStatus, Error> {
+        let before = self.read_device_status(&registers::DEVICE_STATUS)?;
+        self.send_command(registers::STARTUP.addr(), 0)?;
+        let mut changed = false;
+        self.poll_status(timeout, |status| {
+            if status.is_fault() {
+                return Err(Error::DeviceFault(*status));
+            }
+            changed |= *status != before;
+            Ok(match status {
+                DeviceStatus::OnGrid(_) => true,
+                DeviceStatus::Standby(code) => changed && STARTED_STANDBY.contains(code),
+                _ => false
+            })
+        })
+    }
+
+    pub fn power_off(&mut self, timeout: Duration) -> Result<DeviceStatus, Error> {
+        self.send_command(registers::SHUTDOWN.addr(), 0)?;
+        self.wait_for_status(timeout, |status| matches!(status, DeviceStatus::Shutdown(_)))
+    }
+
+    // Polls the device status until `expected` accepts it, failing with the last status
+    // once `timeout` has passed.
+    pub fn wait_for_status<F: FnMut(&DeviceStatus) -> bool>(&mut self, timeout: Duration, mut expected: F) -> Result<DeviceStatus, Error> {
+        self.poll_status(timeout, |status| Ok(expected(status)))
+    }
+
+    fn poll_status<F: FnMut(&DeviceStatus) -> Result<bool, Error>>(&mut self, timeout: Duration, mut expected: F) -> Result<DeviceStatus, Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let status = self.read_device_status(&registers::DEVICE_STATUS)?;
+            if expected(&status)? {
+                return Ok(status);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::StatusTimeout(status));
+            }
+            thread::sleep(STATUS_POLL_INTERVAL.min(deadline - now));
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::{expect_numeric, registers, Error, HuaweiSolar, Register, RegisterTransport};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PvString {
+    // 1-based, matching the PV1..PV24 labels on the inverter.
+    pub index: usize,
+    // `None` where the value could not be read, e.g. it is reported as unavailable.
+    pub voltage: Option<f64>,
+    pub current: Option<f64>,
+    pub power: Option<f64>
+}
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    // Reads voltage and current of every PV string the inverter has. A value that cannot be read
+    // leaves only its own field empty, so the other strings can still be compared.
+    pub fn read_pv_strings(&mut self) -> Result<Vec<PvString>, Error> {
+        let count = self.read_numeric_register(&registers::NUMBER_OF_PV_STRINGS)? as usize;
+        let strings = &registers::PV_STRINGS[..count.min(registers::PV_STRINGS.len())];
+        let regs: Vec<&dyn Register> = strings.iter().flat_map(|&(voltage, current)| [voltage as &dyn Register, current]).collect();
+        let mut values = self.read_many(&regs).into_iter();
+
+        let mut result = Vec::with_capacity(strings.len());
+        for index in 1..=strings.len() {
+            let voltage = expect_numeric(values.next().unwrap()).ok();
+            let current = expect_numeric(values.next().unwrap()).ok();
+            let power = voltage.zip(current).map(|(voltage, current)| (voltage * current).round());
+            result.push(PvString { index, voltage, current, power });
+        }
+        Ok(result)
+    }
+}
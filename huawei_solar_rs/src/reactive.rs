@@ -0,0 +1,53 @@
+use crate::{expect_numeric, registers, Error, HuaweiSolar, Register, RegisterTransport};
+use std::fmt;
+
+// Positive values supply reactive power to the grid, negative ones absorb it. `QsRatio` is
+// the reactive power as a fraction of the apparent power.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReactivePowerControl {
+    PowerFactor(f64),
+    QsRatio(f64)
+}
+
+// Both setpoints are stored independently. The mode selecting which one the inverter holds is
+// not supported yet, as its register address has not been confirmed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReactivePowerSetpoints {
+    pub power_factor: f64,
+    pub qs_ratio: f64
+}
+
+// The inverter cannot hold a power factor below 0.8 in either direction.
+const MIN_POWER_FACTOR: f64 = 0.8;
+
+impl fmt::Display for ReactivePowerControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReactivePowerControl::PowerFactor(power_factor) => write!(f, "fixed power factor {}", power_factor),
+            ReactivePowerControl::QsRatio(ratio) => write!(f, "fixed Q/S ratio {}", ratio)
+        }
+    }
+}
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    pub fn read_reactive_power_setpoints(&mut self) -> Result<ReactivePowerSetpoints, Error> {
+        let regs: [&dyn Register; 2] = [&registers::FIXED_POWER_FACTOR, &registers::REACTIVE_POWER_QS_RATIO];
+        let mut values = self.read_many(&regs).into_iter().map(expect_numeric);
+        let mut next = || values.next().unwrap();
+        Ok(ReactivePowerSetpoints { power_factor: next()?, qs_ratio: next()? })
+    }
+
+    pub fn set_reactive_power_control(&mut self, control: ReactivePowerControl) -> Result<(), Error> {
+        match control {
+            ReactivePowerControl::PowerFactor(power_factor) => {
+                if power_factor.abs() < MIN_POWER_FACTOR {
+                    return Err(Error::InvalidValue(format!("power factor {} is closer to 0 than {}", power_factor, MIN_POWER_FACTOR)));
+                }
+                self.write_register(&registers::FIXED_POWER_FACTOR, power_factor)
+            },
+            ReactivePowerControl::QsRatio(ratio) => self.write_register(&registers::REACTIVE_POWER_QS_RATIO, ratio)
+        }
+    }
+}
@@ -1,9 +1,74 @@
+use crate::BitfieldRegister;
 use crate::StringRegister;
+use crate::WritableRegister;
 use crate::NumericRegister;
 use std::marker::PhantomData;
 
 pub const MODEL_NAME: StringRegister = StringRegister { addr: 30000, count: 15 };
 pub const SERIAL_NUMBER: StringRegister = StringRegister { addr: 30015, count: 10 };
+pub const PRODUCT_NUMBER: StringRegister = StringRegister { addr: 30025, count: 10 };
+pub const FIRMWARE_VERSION: StringRegister = StringRegister { addr: 30035, count: 15 };
+pub const SOFTWARE_VERSION: StringRegister = StringRegister { addr: 30050, count: 15 };
+pub const PROTOCOL_VERSION: NumericRegister<u32> = NumericRegister::<u32> { addr: 30068, count: 2, gain: 1, unit: "", marker: PhantomData };
+pub const MODEL_ID: NumericRegister<u16> = NumericRegister::<u16> { addr: 30070, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const NUMBER_OF_PV_STRINGS: NumericRegister<u16> = NumericRegister::<u16> { addr: 30071, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const NUMBER_OF_MPP_TRACKERS: NumericRegister<u16> = NumericRegister::<u16> { addr: 30072, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const RATED_POWER: NumericRegister<u32> = NumericRegister::<u32> { addr: 30073, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const MAX_ACTIVE_POWER: NumericRegister<u32> = NumericRegister::<u32> { addr: 30075, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const MAX_APPARENT_POWER: NumericRegister<u32> = NumericRegister::<u32> { addr: 30077, count: 2, gain: 1, unit: "VA", marker: PhantomData };
+pub const MAX_REACTIVE_POWER_TO_GRID: NumericRegister<i32> = NumericRegister::<i32> { addr: 30079, count: 2, gain: 1, unit: "var", marker: PhantomData };
+pub const MAX_REACTIVE_POWER_FROM_GRID: NumericRegister<i32> = NumericRegister::<i32> { addr: 30081, count: 2, gain: 1, unit: "var", marker: PhantomData };
+pub const ALARM_1: BitfieldRegister = BitfieldRegister { addr: 32008, count: 1 };
+pub const ALARM_2: BitfieldRegister = BitfieldRegister { addr: 32009, count: 1 };
+pub const ALARM_3: BitfieldRegister = BitfieldRegister { addr: 32010, count: 1 };
+pub const PV1_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32016, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV1_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32017, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV2_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32018, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV2_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32019, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV3_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32020, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV3_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32021, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV4_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32022, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV4_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32023, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV5_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32024, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV5_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32025, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV6_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32026, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV6_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32027, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV7_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32028, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV7_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32029, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV8_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32030, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV8_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32031, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV9_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32032, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV9_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32033, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV10_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32034, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV10_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32035, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV11_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32036, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV11_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32037, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV12_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32038, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV12_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32039, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV13_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32040, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV13_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32041, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV14_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32042, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV14_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32043, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV15_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32044, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV15_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32045, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV16_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32046, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV16_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32047, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV17_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32048, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV17_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32049, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV18_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32050, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV18_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32051, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV19_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32052, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV19_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32053, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV20_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32054, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV20_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32055, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV21_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32056, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV21_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32057, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV22_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32058, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV22_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32059, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV23_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32060, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV23_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32061, count: 1, gain: 100, unit: "A", marker: PhantomData };
+pub const PV24_VOLTAGE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32062, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const PV24_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 32063, count: 1, gain: 100, unit: "A", marker: PhantomData };
 pub const INPUT_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 32064, count: 2, gain: 1, unit: "W", marker: PhantomData };
 pub const GRID_VOLTAGE: NumericRegister<u16> = NumericRegister::<u16> { addr: 32066, count: 1, gain: 10, unit: "V", marker: PhantomData };
 pub const LINE_VOLTAGE_A_B: NumericRegister<u16> = NumericRegister::<u16> { addr: 32066, count: 1, gain: 10, unit: "V", marker: PhantomData };
@@ -25,10 +90,203 @@ pub const EFFICIENCY: NumericRegister<u16> = NumericRegister::<u16> { addr: 3208
 pub const INTERNAL_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 32087, count: 1, gain: 10, unit: "°C", marker: PhantomData };
 pub const INSULATION_RESISTANCE: NumericRegister<u16> = NumericRegister::<u16> { addr: 32088, count: 1, gain: 100, unit: "MOhm", marker: PhantomData };
 pub const DEVICE_STATUS: NumericRegister<u16> = NumericRegister::<u16> { addr: 32089, count: 1, gain: 1, unit: "", marker: PhantomData };
-// FAULT_CODE
+pub const FAULT_CODE: NumericRegister<u16> = NumericRegister::<u16> { addr: 32090, count: 1, gain: 1, unit: "", marker: PhantomData };
 // TODO: timestamps
 pub const STARTUP_TIME: NumericRegister<u32> = NumericRegister::<u32> { addr: 32091, count: 2, gain: 1, unit: "s", marker: PhantomData };
 pub const SHUTDOWN_TIME: NumericRegister<u32> = NumericRegister::<u32> { addr: 32093, count: 2, gain: 1, unit: "s", marker: PhantomData };
 pub const ACCUMULATED_YIELD_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 32106, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
 pub const DAILY_YIELD_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 32114, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
-pub const TIME_ZONE: NumericRegister<i16> = NumericRegister::<i16> { addr: 43006, count: 1, gain: 1, unit: "min", marker: PhantomData };
\ No newline at end of file
+// LUNA2000 storage unit 1
+pub const STORAGE_UNIT_1_RUNNING_STATUS: NumericRegister<u16> = NumericRegister::<u16> { addr: 37000, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_UNIT_1_CHARGE_DISCHARGE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 37001, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_1_BUS_VOLTAGE: NumericRegister<u16> = NumericRegister::<u16> { addr: 37003, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const STORAGE_UNIT_1_STATE_OF_CAPACITY: NumericRegister<u16> = NumericRegister::<u16> { addr: 37004, count: 1, gain: 10, unit: "%", marker: PhantomData };
+pub const STORAGE_UNIT_1_WORKING_MODE: NumericRegister<u16> = NumericRegister::<u16> { addr: 37006, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_UNIT_1_RATED_CHARGE_POWER: NumericRegister<u32> = NumericRegister::<u32> { addr: 37007, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_1_RATED_DISCHARGE_POWER: NumericRegister<u32> = NumericRegister::<u32> { addr: 37009, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_1_FAULT_ID: NumericRegister<u16> = NumericRegister::<u16> { addr: 37014, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_UNIT_1_DAY_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37015, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_1_DAY_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37017, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_1_BUS_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 37021, count: 1, gain: 10, unit: "A", marker: PhantomData };
+pub const STORAGE_UNIT_1_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 37022, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_1_REMAINING_TIME: NumericRegister<u16> = NumericRegister::<u16> { addr: 37025, count: 1, gain: 1, unit: "min", marker: PhantomData };
+pub const STORAGE_UNIT_1_DCDC_VERSION: StringRegister = StringRegister { addr: 37026, count: 10 };
+pub const STORAGE_UNIT_1_BMS_VERSION: StringRegister = StringRegister { addr: 37036, count: 10 };
+pub const STORAGE_UNIT_1_MAX_CHARGE_POWER: NumericRegister<u32> = NumericRegister::<u32> { addr: 37046, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_1_MAX_DISCHARGE_POWER: NumericRegister<u32> = NumericRegister::<u32> { addr: 37048, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_1_SERIAL_NUMBER: StringRegister = StringRegister { addr: 37052, count: 10 };
+pub const STORAGE_UNIT_1_TOTAL_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37066, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_1_TOTAL_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37068, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+// Power meter (DTSU666-H / DDSU666-H) connected to the inverter
+pub const METER_STATUS: NumericRegister<u16> = NumericRegister::<u16> { addr: 37100, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const METER_PHASE_A_VOLTAGE: NumericRegister<i32> = NumericRegister::<i32> { addr: 37101, count: 2, gain: 10, unit: "V", marker: PhantomData };
+pub const METER_PHASE_B_VOLTAGE: NumericRegister<i32> = NumericRegister::<i32> { addr: 37103, count: 2, gain: 10, unit: "V", marker: PhantomData };
+pub const METER_PHASE_C_VOLTAGE: NumericRegister<i32> = NumericRegister::<i32> { addr: 37105, count: 2, gain: 10, unit: "V", marker: PhantomData };
+pub const METER_PHASE_A_CURRENT: NumericRegister<i32> = NumericRegister::<i32> { addr: 37107, count: 2, gain: 100, unit: "A", marker: PhantomData };
+pub const METER_PHASE_B_CURRENT: NumericRegister<i32> = NumericRegister::<i32> { addr: 37109, count: 2, gain: 100, unit: "A", marker: PhantomData };
+pub const METER_PHASE_C_CURRENT: NumericRegister<i32> = NumericRegister::<i32> { addr: 37111, count: 2, gain: 100, unit: "A", marker: PhantomData };
+pub const METER_ACTIVE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 37113, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const METER_REACTIVE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 37115, count: 2, gain: 1, unit: "var", marker: PhantomData };
+pub const METER_POWER_FACTOR: NumericRegister<i16> = NumericRegister::<i16> { addr: 37117, count: 1, gain: 1000, unit: "", marker: PhantomData };
+pub const METER_FREQUENCY: NumericRegister<i16> = NumericRegister::<i16> { addr: 37118, count: 1, gain: 100, unit: "Hz", marker: PhantomData };
+pub const METER_EXPORTED_ENERGY: NumericRegister<i32> = NumericRegister::<i32> { addr: 37119, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const METER_IMPORTED_ENERGY: NumericRegister<i32> = NumericRegister::<i32> { addr: 37121, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const METER_REACTIVE_ENERGY: NumericRegister<i32> = NumericRegister::<i32> { addr: 37123, count: 2, gain: 100, unit: "kvarh", marker: PhantomData };
+pub const METER_TYPE: NumericRegister<u16> = NumericRegister::<u16> { addr: 37125, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const METER_LINE_VOLTAGE_A_B: NumericRegister<i32> = NumericRegister::<i32> { addr: 37126, count: 2, gain: 10, unit: "V", marker: PhantomData };
+pub const METER_LINE_VOLTAGE_B_C: NumericRegister<i32> = NumericRegister::<i32> { addr: 37128, count: 2, gain: 10, unit: "V", marker: PhantomData };
+pub const METER_LINE_VOLTAGE_C_A: NumericRegister<i32> = NumericRegister::<i32> { addr: 37130, count: 2, gain: 10, unit: "V", marker: PhantomData };
+pub const METER_PHASE_A_ACTIVE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 37132, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const METER_PHASE_B_ACTIVE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 37134, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const METER_PHASE_C_ACTIVE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 37136, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const METER_MODEL_DETECTION_RESULT: NumericRegister<u16> = NumericRegister::<u16> { addr: 37138, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const NUMBER_OF_OPTIMIZERS: NumericRegister<u16> = NumericRegister::<u16> { addr: 37200, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const NUMBER_OF_ONLINE_OPTIMIZERS: NumericRegister<u16> = NumericRegister::<u16> { addr: 37201, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const OPTIMIZER_FEATURE_DATA: NumericRegister<u16> = NumericRegister::<u16> { addr: 37202, count: 1, gain: 1, unit: "", marker: PhantomData };
+// LUNA2000 storage unit 2
+pub const STORAGE_UNIT_2_SERIAL_NUMBER: StringRegister = StringRegister { addr: 37700, count: 10 };
+pub const STORAGE_UNIT_2_STATE_OF_CAPACITY: NumericRegister<u16> = NumericRegister::<u16> { addr: 37738, count: 1, gain: 10, unit: "%", marker: PhantomData };
+pub const STORAGE_UNIT_2_RUNNING_STATUS: NumericRegister<u16> = NumericRegister::<u16> { addr: 37741, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_UNIT_2_CHARGE_DISCHARGE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 37743, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_2_DAY_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37746, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_2_DAY_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37748, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_2_BUS_VOLTAGE: NumericRegister<u16> = NumericRegister::<u16> { addr: 37750, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const STORAGE_UNIT_2_BUS_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 37751, count: 1, gain: 10, unit: "A", marker: PhantomData };
+pub const STORAGE_UNIT_2_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 37752, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_2_TOTAL_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37753, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_2_TOTAL_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37755, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+// Both storage units combined
+pub const STORAGE_RATED_CAPACITY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37758, count: 2, gain: 1, unit: "Wh", marker: PhantomData };
+pub const STORAGE_STATE_OF_CAPACITY: NumericRegister<u16> = NumericRegister::<u16> { addr: 37760, count: 1, gain: 10, unit: "%", marker: PhantomData };
+pub const STORAGE_RUNNING_STATUS: NumericRegister<u16> = NumericRegister::<u16> { addr: 37762, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_BUS_VOLTAGE: NumericRegister<u16> = NumericRegister::<u16> { addr: 37763, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const STORAGE_BUS_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 37764, count: 1, gain: 10, unit: "A", marker: PhantomData };
+pub const STORAGE_CHARGE_DISCHARGE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 37765, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_TOTAL_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37780, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_TOTAL_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37782, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_DAY_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37784, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_DAY_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 37786, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+// Battery packs, up to three per storage unit
+pub const STORAGE_UNIT_1_PACK_1_SERIAL_NUMBER: StringRegister = StringRegister { addr: 38200, count: 10 };
+pub const STORAGE_UNIT_1_PACK_1_FIRMWARE_VERSION: StringRegister = StringRegister { addr: 38210, count: 15 };
+pub const STORAGE_UNIT_1_PACK_1_WORKING_STATUS: NumericRegister<u16> = NumericRegister::<u16> { addr: 38228, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_1_STATE_OF_CAPACITY: NumericRegister<u16> = NumericRegister::<u16> { addr: 38229, count: 1, gain: 10, unit: "%", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_1_CHARGE_DISCHARGE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 38233, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_1_VOLTAGE: NumericRegister<u16> = NumericRegister::<u16> { addr: 38235, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_1_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 38236, count: 1, gain: 10, unit: "A", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_1_TOTAL_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38238, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_1_TOTAL_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38240, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_2_SERIAL_NUMBER: StringRegister = StringRegister { addr: 38242, count: 10 };
+pub const STORAGE_UNIT_1_PACK_2_FIRMWARE_VERSION: StringRegister = StringRegister { addr: 38252, count: 15 };
+pub const STORAGE_UNIT_1_PACK_2_WORKING_STATUS: NumericRegister<u16> = NumericRegister::<u16> { addr: 38270, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_2_STATE_OF_CAPACITY: NumericRegister<u16> = NumericRegister::<u16> { addr: 38271, count: 1, gain: 10, unit: "%", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_2_CHARGE_DISCHARGE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 38275, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_2_VOLTAGE: NumericRegister<u16> = NumericRegister::<u16> { addr: 38277, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_2_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 38278, count: 1, gain: 10, unit: "A", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_2_TOTAL_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38280, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_2_TOTAL_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38282, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_3_SERIAL_NUMBER: StringRegister = StringRegister { addr: 38284, count: 10 };
+pub const STORAGE_UNIT_1_PACK_3_FIRMWARE_VERSION: StringRegister = StringRegister { addr: 38294, count: 15 };
+pub const STORAGE_UNIT_1_PACK_3_WORKING_STATUS: NumericRegister<u16> = NumericRegister::<u16> { addr: 38312, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_3_STATE_OF_CAPACITY: NumericRegister<u16> = NumericRegister::<u16> { addr: 38313, count: 1, gain: 10, unit: "%", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_3_CHARGE_DISCHARGE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 38317, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_3_VOLTAGE: NumericRegister<u16> = NumericRegister::<u16> { addr: 38319, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_3_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 38320, count: 1, gain: 10, unit: "A", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_3_TOTAL_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38322, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_3_TOTAL_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38324, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_1_SERIAL_NUMBER: StringRegister = StringRegister { addr: 38326, count: 10 };
+pub const STORAGE_UNIT_2_PACK_1_FIRMWARE_VERSION: StringRegister = StringRegister { addr: 38336, count: 15 };
+pub const STORAGE_UNIT_2_PACK_1_WORKING_STATUS: NumericRegister<u16> = NumericRegister::<u16> { addr: 38354, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_1_STATE_OF_CAPACITY: NumericRegister<u16> = NumericRegister::<u16> { addr: 38355, count: 1, gain: 10, unit: "%", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_1_CHARGE_DISCHARGE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 38359, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_1_VOLTAGE: NumericRegister<u16> = NumericRegister::<u16> { addr: 38361, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_1_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 38362, count: 1, gain: 10, unit: "A", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_1_TOTAL_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38364, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_1_TOTAL_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38366, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_2_SERIAL_NUMBER: StringRegister = StringRegister { addr: 38368, count: 10 };
+pub const STORAGE_UNIT_2_PACK_2_FIRMWARE_VERSION: StringRegister = StringRegister { addr: 38378, count: 15 };
+pub const STORAGE_UNIT_2_PACK_2_WORKING_STATUS: NumericRegister<u16> = NumericRegister::<u16> { addr: 38396, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_2_STATE_OF_CAPACITY: NumericRegister<u16> = NumericRegister::<u16> { addr: 38397, count: 1, gain: 10, unit: "%", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_2_CHARGE_DISCHARGE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 38401, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_2_VOLTAGE: NumericRegister<u16> = NumericRegister::<u16> { addr: 38403, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_2_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 38404, count: 1, gain: 10, unit: "A", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_2_TOTAL_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38406, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_2_TOTAL_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38408, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_3_SERIAL_NUMBER: StringRegister = StringRegister { addr: 38410, count: 10 };
+pub const STORAGE_UNIT_2_PACK_3_FIRMWARE_VERSION: StringRegister = StringRegister { addr: 38420, count: 15 };
+pub const STORAGE_UNIT_2_PACK_3_WORKING_STATUS: NumericRegister<u16> = NumericRegister::<u16> { addr: 38438, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_3_STATE_OF_CAPACITY: NumericRegister<u16> = NumericRegister::<u16> { addr: 38439, count: 1, gain: 10, unit: "%", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_3_CHARGE_DISCHARGE_POWER: NumericRegister<i32> = NumericRegister::<i32> { addr: 38443, count: 2, gain: 1, unit: "W", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_3_VOLTAGE: NumericRegister<u16> = NumericRegister::<u16> { addr: 38445, count: 1, gain: 10, unit: "V", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_3_CURRENT: NumericRegister<i16> = NumericRegister::<i16> { addr: 38446, count: 1, gain: 10, unit: "A", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_3_TOTAL_CHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38448, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_3_TOTAL_DISCHARGE_ENERGY: NumericRegister<u32> = NumericRegister::<u32> { addr: 38450, count: 2, gain: 100, unit: "kWh", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_1_MAX_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38452, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_1_MIN_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38453, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_2_MAX_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38454, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_2_MIN_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38455, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_3_MAX_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38456, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_1_PACK_3_MIN_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38457, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_1_MAX_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38458, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_1_MIN_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38459, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_2_MAX_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38460, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_2_MIN_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38461, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_3_MAX_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38462, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+pub const STORAGE_UNIT_2_PACK_3_MIN_TEMPERATURE: NumericRegister<i16> = NumericRegister::<i16> { addr: 38463, count: 1, gain: 10, unit: "°C", marker: PhantomData };
+// Local time of the inverter: seconds since the epoch, shifted by TIME_ZONE
+pub const SYSTEM_TIME: NumericRegister<u32> = NumericRegister::<u32> { addr: 40000, count: 2, gain: 1, unit: "s", marker: PhantomData };
+// Active power control. The inverter applies the lowest of the derating limits.
+pub const ACTIVE_POWER_PERCENTAGE_DERATING: WritableRegister<i16> = WritableRegister::<i16> { register: NumericRegister::<i16> { addr: 40125, count: 1, gain: 10, unit: "%", marker: PhantomData }, min: 0.0, max: 100.0 };
+pub const ACTIVE_POWER_FIXED_DERATING: WritableRegister<u32> = WritableRegister::<u32> { register: NumericRegister::<u32> { addr: 40126, count: 2, gain: 1, unit: "W", marker: PhantomData }, min: 0.0, max: 4294967294.0 };
+// Reactive power control. Positive values supply reactive power to the grid (over-excited).
+pub const FIXED_POWER_FACTOR: WritableRegister<i16> = WritableRegister::<i16> { register: NumericRegister::<i16> { addr: 40122, count: 1, gain: 1000, unit: "", marker: PhantomData }, min: -1.0, max: 1.0 };
+pub const REACTIVE_POWER_QS_RATIO: WritableRegister<i16> = WritableRegister::<i16> { register: NumericRegister::<i16> { addr: 40123, count: 1, gain: 1000, unit: "", marker: PhantomData }, min: -1.0, max: 1.0 };
+// Write-only commands, issued by writing 0
+pub const STARTUP: NumericRegister<u16> = NumericRegister::<u16> { addr: 40200, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const SHUTDOWN: NumericRegister<u16> = NumericRegister::<u16> { addr: 40201, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const TIME_ZONE: WritableRegister<i16> = WritableRegister::<i16> { register: NumericRegister::<i16> { addr: 43006, count: 1, gain: 1, unit: "min", marker: PhantomData }, min: -720.0, max: 840.0 };
+// LUNA2000 battery control
+pub const STORAGE_MAXIMUM_CHARGING_POWER: WritableRegister<u32> = WritableRegister::<u32> { register: NumericRegister::<u32> { addr: 47075, count: 2, gain: 1, unit: "W", marker: PhantomData }, min: 0.0, max: 4294967294.0 };
+pub const STORAGE_MAXIMUM_DISCHARGING_POWER: WritableRegister<u32> = WritableRegister::<u32> { register: NumericRegister::<u32> { addr: 47077, count: 2, gain: 1, unit: "W", marker: PhantomData }, min: 0.0, max: 4294967294.0 };
+pub const STORAGE_CHARGING_CUTOFF_CAPACITY: WritableRegister<u16> = WritableRegister::<u16> { register: NumericRegister::<u16> { addr: 47081, count: 1, gain: 10, unit: "%", marker: PhantomData }, min: 90.0, max: 100.0 };
+pub const STORAGE_DISCHARGING_CUTOFF_CAPACITY: WritableRegister<u16> = WritableRegister::<u16> { register: NumericRegister::<u16> { addr: 47082, count: 1, gain: 10, unit: "%", marker: PhantomData }, min: 0.0, max: 20.0 };
+pub const STORAGE_FORCIBLE_CHARGE_DISCHARGE_PERIOD: WritableRegister<u16> = WritableRegister::<u16> { register: NumericRegister::<u16> { addr: 47083, count: 1, gain: 1, unit: "min", marker: PhantomData }, min: 0.0, max: 1440.0 };
+pub const STORAGE_WORKING_MODE_SETTING: WritableRegister<u16> = WritableRegister::<u16> { register: NumericRegister::<u16> { addr: 47086, count: 1, gain: 1, unit: "", marker: PhantomData }, min: 0.0, max: 5.0 };
+// Write-only: 0 stops, 1 starts charging and 2 starts discharging
+pub const STORAGE_FORCIBLE_CHARGE_DISCHARGE: NumericRegister<u16> = NumericRegister::<u16> { addr: 47100, count: 1, gain: 1, unit: "", marker: PhantomData };
+pub const STORAGE_FORCIBLE_CHARGE_DISCHARGE_SOC: WritableRegister<u16> = WritableRegister::<u16> { register: NumericRegister::<u16> { addr: 47101, count: 1, gain: 10, unit: "%", marker: PhantomData }, min: 12.0, max: 100.0 };
+pub const STORAGE_FORCIBLE_CHARGE_DISCHARGE_SETTING_MODE: WritableRegister<u16> = WritableRegister::<u16> { register: NumericRegister::<u16> { addr: 47246, count: 1, gain: 1, unit: "", marker: PhantomData }, min: 0.0, max: 1.0 };
+pub const STORAGE_FORCIBLE_CHARGE_POWER: WritableRegister<u32> = WritableRegister::<u32> { register: NumericRegister::<u32> { addr: 47247, count: 2, gain: 1, unit: "W", marker: PhantomData }, min: 0.0, max: 4294967294.0 };
+pub const STORAGE_FORCIBLE_DISCHARGE_POWER: WritableRegister<u32> = WritableRegister::<u32> { register: NumericRegister::<u32> { addr: 47249, count: 2, gain: 1, unit: "W", marker: PhantomData }, min: 0.0, max: 4294967294.0 };
+// Grid export limitation, which needs a power meter
+pub const ACTIVE_POWER_CONTROL_MODE: WritableRegister<u16> = WritableRegister::<u16> { register: NumericRegister::<u16> { addr: 47415, count: 1, gain: 1, unit: "", marker: PhantomData }, min: 0.0, max: 7.0 };
+pub const MAXIMUM_FEED_GRID_POWER: WritableRegister<i32> = WritableRegister::<i32> { register: NumericRegister::<i32> { addr: 47416, count: 2, gain: 1, unit: "W", marker: PhantomData }, min: 0.0, max: 2147483646.0 };
+pub const MAXIMUM_FEED_GRID_POWER_PERCENT: WritableRegister<i16> = WritableRegister::<i16> { register: NumericRegister::<i16> { addr: 47418, count: 1, gain: 10, unit: "%", marker: PhantomData }, min: 0.0, max: 100.0 };
+
+// Voltage and current register of every PV string, in string order.
+pub const PV_STRINGS: [(&NumericRegister<i16>, &NumericRegister<i16>); 24] = [
+    (&PV1_VOLTAGE, &PV1_CURRENT),
+    (&PV2_VOLTAGE, &PV2_CURRENT),
+    (&PV3_VOLTAGE, &PV3_CURRENT),
+    (&PV4_VOLTAGE, &PV4_CURRENT),
+    (&PV5_VOLTAGE, &PV5_CURRENT),
+    (&PV6_VOLTAGE, &PV6_CURRENT),
+    (&PV7_VOLTAGE, &PV7_CURRENT),
+    (&PV8_VOLTAGE, &PV8_CURRENT),
+    (&PV9_VOLTAGE, &PV9_CURRENT),
+    (&PV10_VOLTAGE, &PV10_CURRENT),
+    (&PV11_VOLTAGE, &PV11_CURRENT),
+    (&PV12_VOLTAGE, &PV12_CURRENT),
+    (&PV13_VOLTAGE, &PV13_CURRENT),
+    (&PV14_VOLTAGE, &PV14_CURRENT),
+    (&PV15_VOLTAGE, &PV15_CURRENT),
+    (&PV16_VOLTAGE, &PV16_CURRENT),
+    (&PV17_VOLTAGE, &PV17_CURRENT),
+    (&PV18_VOLTAGE, &PV18_CURRENT),
+    (&PV19_VOLTAGE, &PV19_CURRENT),
+    (&PV20_VOLTAGE, &PV20_CURRENT),
+    (&PV21_VOLTAGE, &PV21_CURRENT),
+    (&PV22_VOLTAGE, &PV22_CURRENT),
+    (&PV23_VOLTAGE, &PV23_CURRENT),
+    (&PV24_VOLTAGE, &PV24_CURRENT),
+];
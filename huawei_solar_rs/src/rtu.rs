@@ -0,0 +1,129 @@
+use crate::{pdu, Error, RegisterTransport};
+use std::io::{Read, Write};
+#[cfg(feature = "serial")]
+use std::time;
+
+#[cfg(feature = "serial")]
+pub use serialport::{Parity, StopBits};
+
+#[cfg(feature = "serial")]
+#[derive(Clone)]
+pub struct SerialConfig {
+    pub baud_rate: u32,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+    pub timeout: time::Duration
+}
+
+// SUN2000 COM ports default to 9600 baud, 8N1.
+#[cfg(feature = "serial")]
+impl Default for SerialConfig {
+    fn default() -> SerialConfig {
+        SerialConfig {
+            baud_rate: 9600,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            timeout: time::Duration::from_millis(1000)
+        }
+    }
+}
+
+// Modbus RTU framing (slave address, PDU, CRC) over any byte stream, e.g. a serial port
+// or a pseudo-terminal.
+pub struct RtuTransport<S: Read + Write> {
+    stream: S,
+    slave_id: u8
+}
+
+#[cfg(feature = "serial")]
+impl RtuTransport<Box<dyn serialport::SerialPort>> {
+    pub fn open(path: &str, slave_id: u8, config: &SerialConfig) -> Result<RtuTransport<Box<dyn serialport::SerialPort>>, Error> {
+        let port = serialport::new(path, config.baud_rate)
+            .data_bits(serialport::DataBits::Eight)
+            .parity(config.parity)
+            .stop_bits(config.stop_bits)
+            .timeout(config.timeout)
+            .open()
+            .map_err(|e| Error::Connection(e.into()))?;
+        Ok(RtuTransport::new(port, slave_id))
+    }
+}
+
+impl<S: Read + Write> RtuTransport<S> {
+    pub fn new(stream: S, slave_id: u8) -> RtuTransport<S> {
+        RtuTransport { stream, slave_id }
+    }
+
+    pub fn slave_id(&self) -> u8 {
+        self.slave_id
+    }
+
+    fn request(&mut self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut frame = vec![self.slave_id];
+        frame.extend_from_slice(request);
+        let crc = pdu::crc16(&frame);
+        frame.extend_from_slice(&crc.to_le_bytes());
+        self.stream.write_all(&frame)?;
+        self.read_response()
+    }
+
+    // RTU frames carry no length field, so the expected size is derived from the function code.
+    fn read_response(&mut self) -> Result<Vec<u8>, Error> {
+        let mut frame = vec![0u8; 3];
+        self.stream.read_exact(&mut frame)?;
+        let function = frame[1];
+        let remaining = if function & 0x80 != 0 {
+            2
+        } else {
+            match function {
+                pdu::READ_HOLDING_REGISTERS => frame[2] as usize + 2,
+                pdu::WRITE_SINGLE_REGISTER | pdu::WRITE_MULTIPLE_REGISTERS => 5,
+                // Sub-function, then a length byte.
+                pdu::HUAWEI_FILE => {
+                    let mut length = [0u8; 1];
+                    self.stream.read_exact(&mut length)?;
+                    frame.push(length[0]);
+                    length[0] as usize + 2
+                },
+                _ => return Err(Error::InvalidResponse(format!("unexpected function code 0x{:02X}", function)))
+            }
+        };
+        let mut rest = vec![0u8; remaining];
+        self.stream.read_exact(&mut rest)?;
+        frame.extend_from_slice(&rest);
+
+        let (body, crc) = frame.split_at(frame.len() - 2);
+        let expected = pdu::crc16(body);
+        let actual = u16::from_le_bytes([crc[0], crc[1]]);
+        if expected != actual {
+            return Err(Error::CrcMismatch { expected, actual });
+        }
+        if body[0] != self.slave_id {
+            return Err(Error::InvalidResponse(format!("response from slave {} instead of {}", body[0], self.slave_id)));
+        }
+        Ok(body[1..].to_vec())
+    }
+}
+
+impl<S: Read + Write> RegisterTransport for RtuTransport<S> {
+    fn read_holding_registers(&mut self, addr: u16, count: u16) -> Result<Vec<u16>, Error> {
+        let response = self.request(&pdu::read_holding_registers_request(addr, count))?;
+        pdu::parse_read_holding_registers_response(&response, count)
+    }
+
+    fn write_single_register(&mut self, addr: u16, value: u16) -> Result<(), Error> {
+        let request = pdu::write_single_register_request(addr, value);
+        let response = self.request(&request)?;
+        pdu::check_write_response(&request, &response)
+    }
+
+    fn write_multiple_registers(&mut self, addr: u16, values: &[u16]) -> Result<(), Error> {
+        let request = pdu::write_multiple_registers_request(addr, values);
+        let response = self.request(&request)?;
+        pdu::check_write_response(&request, &response)
+    }
+
+    fn raw_request(&mut self, pdu: &[u8]) -> Result<Vec<u8>, Error> {
+        self.request(pdu)
+    }
+}
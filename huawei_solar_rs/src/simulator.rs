@@ -0,0 +1,451 @@
+use crate::{pdu, registers, Error, ExceptionCode, Register, RegisterValue};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::{thread, time};
+
+// Fake SUN2000 serving the register map over Modbus TCP, meant for tests and offline tooling.
+pub struct Simulator {
+    state: State,
+    slave_ids: Vec<u8>,
+    latency: time::Duration,
+    drop_after: Option<usize>
+}
+
+struct State {
+    registers: BTreeMap<u16, u16>,
+    exceptions: BTreeMap<u16, ExceptionCode>,
+    ignored_writes: BTreeSet<u16>,
+    write_effects: BTreeMap<u16, (u16, Vec<u16>)>
+}
+
+struct Shared {
+    state: Mutex<State>,
+    slave_ids: Vec<u8>,
+    latency: time::Duration,
+    drop_after: Option<usize>,
+    stopped: AtomicBool
+}
+
+pub struct SimulatorHandle {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    thread: Option<thread::JoinHandle<()>>
+}
+
+impl Default for Simulator {
+    fn default() -> Simulator {
+        Simulator::new()
+    }
+}
+
+impl Simulator {
+    // Simulator answering on slave ids 0 and 1 with plausible values for every register
+    // in `registers`.
+    pub fn new() -> Simulator {
+        let mut simulator = Simulator::empty();
+        for (reg, value) in default_values() {
+            simulator.set_value(reg, &value).unwrap();
+        }
+        simulator
+    }
+
+    // Simulator with no register values. Unset registers read as 0.
+    pub fn empty() -> Simulator {
+        Simulator {
+            state: State { registers: BTreeMap::new(), exceptions: BTreeMap::new(), ignored_writes: BTreeSet::new(), write_effects: BTreeMap::new() },
+            slave_ids: vec![0, 1],
+            latency: time::Duration::ZERO,
+            drop_after: None
+        }
+    }
+
+    pub fn set_value(&mut self, reg: &dyn Register, value: &RegisterValue) -> Result<&mut Simulator, Error> {
+        self.state.set(reg.addr(), &reg.encode(value)?);
+        Ok(self)
+    }
+
+    pub fn set_words(&mut self, addr: u16, words: &[u16]) -> &mut Simulator {
+        self.state.set(addr, words);
+        self
+    }
+
+    // Requests touching `addr` are answered with the given exception.
+    pub fn inject_exception(&mut self, addr: u16, code: ExceptionCode) -> &mut Simulator {
+        self.state.exceptions.insert(addr, code);
+        self
+    }
+
+    // Writes to `addr` are acknowledged but not applied, like a setting the inverter rejects
+    // silently.
+    pub fn ignore_writes(&mut self, addr: u16) -> &mut Simulator {
+        self.state.ignored_writes.insert(addr);
+        self
+    }
+
+    // A write to `addr` also sets the registers at `target` to `words`, to fake the effect of a
+    // command such as startup or shutdown.
+    pub fn on_write(&mut self, addr: u16, target: u16, words: &[u16]) -> &mut Simulator {
+        self.state.write_effects.insert(addr, (target, words.to_vec()));
+        self
+    }
+
+    // Requests for other slave ids are answered with "gateway target failed to respond",
+    // like the S-Dongle does.
+    pub fn slave_ids(&mut self, slave_ids: &[u8]) -> &mut Simulator {
+        self.slave_ids = slave_ids.to_vec();
+        self
+    }
+
+    // Delay added before every response.
+    pub fn latency(&mut self, latency: time::Duration) -> &mut Simulator {
+        self.latency = latency;
+        self
+    }
+
+    // Closes each connection after it has been served this many requests.
+    pub fn drop_connections_after(&mut self, requests: usize) -> &mut Simulator {
+        self.drop_after = Some(requests);
+        self
+    }
+
+    pub fn start<A: ToSocketAddrs>(self, addr: A) -> Result<SimulatorHandle, Error> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            state: Mutex::new(self.state),
+            slave_ids: self.slave_ids,
+            latency: self.latency,
+            drop_after: self.drop_after,
+            stopped: AtomicBool::new(false)
+        });
+        let accept_shared = shared.clone();
+        let thread = thread::spawn(move || accept_connections(listener, accept_shared));
+        Ok(SimulatorHandle { addr, shared, thread: Some(thread) })
+    }
+}
+
+impl SimulatorHandle {
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn set_value(&self, reg: &dyn Register, value: &RegisterValue) -> Result<(), Error> {
+        let words = reg.encode(value)?;
+        self.shared.state.lock().unwrap().set(reg.addr(), &words);
+        Ok(())
+    }
+
+    pub fn words(&self, addr: u16, count: u16) -> Vec<u16> {
+        self.shared.state.lock().unwrap().get(addr, count)
+    }
+
+    pub fn inject_exception(&self, addr: u16, code: ExceptionCode) {
+        self.shared.state.lock().unwrap().exceptions.insert(addr, code);
+    }
+
+    pub fn clear_exception(&self, addr: u16) {
+        self.shared.state.lock().unwrap().exceptions.remove(&addr);
+    }
+
+    // Blocks for as long as the process runs, serving requests. Use this to run the simulator
+    // on its own; to stop it later, keep the handle and drop it instead.
+    pub fn wait(mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for SimulatorHandle {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl State {
+    fn set(&mut self, addr: u16, words: &[u16]) {
+        for (i, &word) in words.iter().enumerate() {
+            self.registers.insert(addr.wrapping_add(i as u16), word);
+        }
+    }
+
+    fn write(&mut self, addr: u16, words: &[u16]) {
+        if (0..words.len()).any(|i| self.ignored_writes.contains(&addr.wrapping_add(i as u16))) {
+            return;
+        }
+        self.set(addr, words);
+        if let Some((target, effect)) = self.write_effects.get(&addr).cloned() {
+            self.set(target, &effect);
+        }
+    }
+
+    fn get(&self, addr: u16, count: u16) -> Vec<u16> {
+        (0..count).map(|i| *self.registers.get(&addr.wrapping_add(i)).unwrap_or(&0)).collect()
+    }
+
+    fn exception(&self, addr: u16, count: u16) -> Option<ExceptionCode> {
+        let end = addr as u32 + count as u32;
+        self.exceptions.iter().find(|(&a, _)| a >= addr && (a as u32) < end).map(|(_, &code)| code)
+    }
+}
+
+impl Shared {
+    fn handle(&self, slave_id: u8, request: &[u8]) -> Vec<u8> {
+        let function = request[0];
+        if !self.slave_ids.contains(&slave_id) {
+            return exception_response(function, ExceptionCode::GatewayTargetFailedToRespond);
+        }
+        match self.process(request) {
+            Ok(response) => response,
+            Err(code) => exception_response(function, code)
+        }
+    }
+
+    fn process(&self, request: &[u8]) -> Result<Vec<u8>, ExceptionCode> {
+        let field = |i: usize| -> Result<u16, ExceptionCode> {
+            match request.get(i..i + 2) {
+                Some(b) => Ok(u16::from_be_bytes([b[0], b[1]])),
+                None => Err(ExceptionCode::IllegalDataValue)
+            }
+        };
+        let mut state = self.state.lock().unwrap();
+        match request[0] {
+            pdu::READ_HOLDING_REGISTERS => {
+                let (addr, count) = (field(1)?, field(3)?);
+                if count == 0 || count > 125 {
+                    return Err(ExceptionCode::IllegalDataValue);
+                }
+                if let Some(code) = state.exception(addr, count) {
+                    return Err(code);
+                }
+                let mut response = vec![pdu::READ_HOLDING_REGISTERS, (count * 2) as u8];
+                for word in state.get(addr, count) {
+                    response.extend_from_slice(&word.to_be_bytes());
+                }
+                Ok(response)
+            },
+            pdu::WRITE_SINGLE_REGISTER => {
+                let (addr, value) = (field(1)?, field(3)?);
+                if let Some(code) = state.exception(addr, 1) {
+                    return Err(code);
+                }
+                state.write(addr, &[value]);
+                Ok(request[..5].to_vec())
+            },
+            pdu::WRITE_MULTIPLE_REGISTERS => {
+                let (addr, count) = (field(1)?, field(3)?);
+                let values = (0..count as usize).map(|i| field(6 + i * 2)).collect::<Result<Vec<u16>, ExceptionCode>>()?;
+                if let Some(code) = state.exception(addr, count) {
+                    return Err(code);
+                }
+                state.write(addr, &values);
+                Ok(request[..5].to_vec())
+            },
+            _ => Err(ExceptionCode::IllegalFunction)
+        }
+    }
+}
+
+fn exception_response(function: u8, code: ExceptionCode) -> Vec<u8> {
+    vec![function | 0x80, code.code()]
+}
+
+fn accept_connections(listener: TcpListener, shared: Arc<Shared>) {
+    while !shared.stopped.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    let _ = serve(stream, &shared);
+                });
+            },
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(time::Duration::from_millis(10)),
+            Err(_) => break
+        }
+    }
+}
+
+fn serve(mut stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(time::Duration::from_millis(100)))?;
+    let mut served = 0;
+    loop {
+        let mut header = [0u8; pdu::MBAP_HEADER_SIZE];
+        if !read_full(&mut stream, &mut header, shared)? {
+            return Ok(());
+        }
+        let (tid, len) = match pdu::parse_mbap_header(&header) {
+            Ok(header) => header,
+            Err(_) => return stream.shutdown(Shutdown::Both)
+        };
+        let mut request = vec![0u8; len];
+        if !read_full(&mut stream, &mut request, shared)? {
+            return Ok(());
+        }
+
+        let response = shared.handle(header[6], &request);
+        thread::sleep(shared.latency);
+        let mut frame = pdu::mbap_header(tid, header[6], response.len()).to_vec();
+        frame.extend_from_slice(&response);
+        stream.write_all(&frame)?;
+
+        served += 1;
+        if shared.drop_after == Some(served) {
+            return stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+// Fills `buf`, giving up when the simulator stops. Returns false if the peer closed the connection.
+fn read_full(stream: &mut TcpStream, buf: &mut [u8], shared: &Shared) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        if shared.stopped.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        match stream.read(&mut buf[filled..]) {
+            Ok(0) => return Ok(false),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => {},
+            Err(e) => return Err(e)
+        }
+    }
+    Ok(true)
+}
+
+fn default_values() -> Vec<(&'static dyn Register, RegisterValue)> {
+    use RegisterValue::{Numeric, String};
+    vec![
+        (&registers::MODEL_NAME, String("SUN2000-5KTL-M1".to_string())),
+        (&registers::SERIAL_NUMBER, String("SIM0000001".to_string())),
+        (&registers::PRODUCT_NUMBER, String("01074425".to_string())),
+        (&registers::FIRMWARE_VERSION, String("V100R001C00SPC124".to_string())),
+        (&registers::SOFTWARE_VERSION, String("V100R001C00SPC133".to_string())),
+        (&registers::PROTOCOL_VERSION, Numeric(0x0003_0001 as f64)),
+        (&registers::MODEL_ID, Numeric(428.0)),
+        (&registers::NUMBER_OF_PV_STRINGS, Numeric(2.0)),
+        (&registers::NUMBER_OF_MPP_TRACKERS, Numeric(2.0)),
+        (&registers::RATED_POWER, Numeric(5000.0)),
+        (&registers::MAX_ACTIVE_POWER, Numeric(5500.0)),
+        (&registers::MAX_APPARENT_POWER, Numeric(5500.0)),
+        (&registers::MAX_REACTIVE_POWER_TO_GRID, Numeric(3300.0)),
+        (&registers::MAX_REACTIVE_POWER_FROM_GRID, Numeric(-3300.0)),
+        (&registers::ALARM_1, Numeric(0.0)),
+        (&registers::ALARM_2, Numeric(0.0)),
+        (&registers::ALARM_3, Numeric(0.0)),
+        (&registers::PV1_VOLTAGE, Numeric(361.4)),
+        (&registers::PV1_CURRENT, Numeric(7.62)),
+        (&registers::PV2_VOLTAGE, Numeric(358.9)),
+        (&registers::PV2_CURRENT, Numeric(6.59)),
+        (&registers::INPUT_POWER, Numeric(5120.0)),
+        (&registers::LINE_VOLTAGE_A_B, Numeric(400.1)),
+        (&registers::LINE_VOLTAGE_B_C, Numeric(399.8)),
+        (&registers::LINE_VOLTAGE_C_A, Numeric(400.4)),
+        (&registers::PHASE_A_VOLTAGE, Numeric(230.9)),
+        (&registers::PHASE_B_VOLTAGE, Numeric(231.2)),
+        (&registers::PHASE_C_VOLTAGE, Numeric(230.4)),
+        (&registers::PHASE_A_CURRENT, Numeric(7.215)),
+        (&registers::PHASE_B_CURRENT, Numeric(7.198)),
+        (&registers::PHASE_C_CURRENT, Numeric(7.204)),
+        (&registers::DAY_ACTIVE_POWER_PEAK, Numeric(5230.0)),
+        (&registers::ACTIVE_POWER, Numeric(4980.0)),
+        (&registers::REACTIVE_POWER, Numeric(12.0)),
+        (&registers::POWER_FACTOR, Numeric(0.999)),
+        (&registers::GRID_FREQUENCY, Numeric(50.01)),
+        (&registers::EFFICIENCY, Numeric(98.2)),
+        (&registers::INTERNAL_TEMPERATURE, Numeric(41.3)),
+        (&registers::INSULATION_RESISTANCE, Numeric(3.5)),
+        (&registers::DEVICE_STATUS, Numeric(0x0200 as f64)),
+        (&registers::FAULT_CODE, Numeric(0.0)),
+        (&registers::STARTUP_TIME, Numeric(1_700_000_000.0)),
+        (&registers::SHUTDOWN_TIME, Numeric(1_699_960_000.0)),
+        (&registers::ACCUMULATED_YIELD_ENERGY, Numeric(12345.67)),
+        (&registers::DAILY_YIELD_ENERGY, Numeric(23.45)),
+        (&registers::STORAGE_UNIT_1_RUNNING_STATUS, Numeric(2.0)),
+        (&registers::STORAGE_UNIT_1_CHARGE_DISCHARGE_POWER, Numeric(1500.0)),
+        (&registers::STORAGE_UNIT_1_BUS_VOLTAGE, Numeric(452.3)),
+        (&registers::STORAGE_UNIT_1_STATE_OF_CAPACITY, Numeric(64.5)),
+        (&registers::STORAGE_UNIT_1_WORKING_MODE, Numeric(4.0)),
+        (&registers::STORAGE_UNIT_1_RATED_CHARGE_POWER, Numeric(5000.0)),
+        (&registers::STORAGE_UNIT_1_RATED_DISCHARGE_POWER, Numeric(5000.0)),
+        (&registers::STORAGE_UNIT_1_DAY_CHARGE_ENERGY, Numeric(4.21)),
+        (&registers::STORAGE_UNIT_1_DAY_DISCHARGE_ENERGY, Numeric(1.37)),
+        (&registers::STORAGE_UNIT_1_BUS_CURRENT, Numeric(3.3)),
+        (&registers::STORAGE_UNIT_1_TEMPERATURE, Numeric(24.6)),
+        (&registers::STORAGE_UNIT_1_SERIAL_NUMBER, String("SIMBAT0001".to_string())),
+        (&registers::STORAGE_UNIT_1_TOTAL_CHARGE_ENERGY, Numeric(1520.44)),
+        (&registers::STORAGE_UNIT_1_TOTAL_DISCHARGE_ENERGY, Numeric(1408.12)),
+        (&registers::METER_STATUS, Numeric(1.0)),
+        (&registers::METER_PHASE_A_VOLTAGE, Numeric(231.4)),
+        (&registers::METER_PHASE_B_VOLTAGE, Numeric(230.8)),
+        (&registers::METER_PHASE_C_VOLTAGE, Numeric(232.0)),
+        (&registers::METER_PHASE_A_CURRENT, Numeric(4.12)),
+        (&registers::METER_PHASE_B_CURRENT, Numeric(3.98)),
+        (&registers::METER_PHASE_C_CURRENT, Numeric(4.05)),
+        (&registers::METER_ACTIVE_POWER, Numeric(2790.0)),
+        (&registers::METER_REACTIVE_POWER, Numeric(-120.0)),
+        (&registers::METER_POWER_FACTOR, Numeric(0.998)),
+        (&registers::METER_FREQUENCY, Numeric(50.01)),
+        (&registers::METER_EXPORTED_ENERGY, Numeric(8210.55)),
+        (&registers::METER_IMPORTED_ENERGY, Numeric(3120.8)),
+        (&registers::METER_REACTIVE_ENERGY, Numeric(412.3)),
+        (&registers::METER_TYPE, Numeric(1.0)),
+        (&registers::METER_LINE_VOLTAGE_A_B, Numeric(400.2)),
+        (&registers::METER_LINE_VOLTAGE_B_C, Numeric(399.9)),
+        (&registers::METER_LINE_VOLTAGE_C_A, Numeric(401.1)),
+        (&registers::METER_PHASE_A_ACTIVE_POWER, Numeric(940.0)),
+        (&registers::METER_PHASE_B_ACTIVE_POWER, Numeric(910.0)),
+        (&registers::METER_PHASE_C_ACTIVE_POWER, Numeric(940.0)),
+        (&registers::METER_MODEL_DETECTION_RESULT, Numeric(1.0)),
+        (&registers::NUMBER_OF_OPTIMIZERS, Numeric(12.0)),
+        (&registers::NUMBER_OF_ONLINE_OPTIMIZERS, Numeric(12.0)),
+        (&registers::OPTIMIZER_FEATURE_DATA, Numeric(0.0)),
+        (&registers::STORAGE_RATED_CAPACITY, Numeric(5000.0)),
+        (&registers::STORAGE_STATE_OF_CAPACITY, Numeric(64.5)),
+        (&registers::STORAGE_RUNNING_STATUS, Numeric(2.0)),
+        (&registers::STORAGE_BUS_VOLTAGE, Numeric(452.3)),
+        (&registers::STORAGE_BUS_CURRENT, Numeric(3.3)),
+        (&registers::STORAGE_CHARGE_DISCHARGE_POWER, Numeric(1500.0)),
+        (&registers::STORAGE_TOTAL_CHARGE_ENERGY, Numeric(1520.44)),
+        (&registers::STORAGE_TOTAL_DISCHARGE_ENERGY, Numeric(1408.12)),
+        (&registers::STORAGE_DAY_CHARGE_ENERGY, Numeric(4.21)),
+        (&registers::STORAGE_DAY_DISCHARGE_ENERGY, Numeric(1.37)),
+        (&registers::STORAGE_UNIT_1_PACK_1_SERIAL_NUMBER, String("SIMPACK001".to_string())),
+        (&registers::STORAGE_UNIT_1_PACK_1_WORKING_STATUS, Numeric(2.0)),
+        (&registers::STORAGE_UNIT_1_PACK_1_STATE_OF_CAPACITY, Numeric(64.5)),
+        (&registers::STORAGE_UNIT_1_PACK_1_CHARGE_DISCHARGE_POWER, Numeric(1500.0)),
+        (&registers::STORAGE_UNIT_1_PACK_1_VOLTAGE, Numeric(452.3)),
+        (&registers::STORAGE_UNIT_1_PACK_1_CURRENT, Numeric(3.3)),
+        (&registers::STORAGE_UNIT_1_PACK_1_TOTAL_CHARGE_ENERGY, Numeric(1520.44)),
+        (&registers::STORAGE_UNIT_1_PACK_1_TOTAL_DISCHARGE_ENERGY, Numeric(1408.12)),
+        (&registers::STORAGE_UNIT_1_PACK_1_MAX_TEMPERATURE, Numeric(25.1)),
+        (&registers::STORAGE_UNIT_1_PACK_1_MIN_TEMPERATURE, Numeric(23.8)),
+        (&registers::SYSTEM_TIME, Numeric(1_700_003_600.0)),
+        (&registers::ACTIVE_POWER_PERCENTAGE_DERATING, Numeric(100.0)),
+        (&registers::ACTIVE_POWER_FIXED_DERATING, Numeric(5500.0)),
+        (&registers::FIXED_POWER_FACTOR, Numeric(1.0)),
+        (&registers::REACTIVE_POWER_QS_RATIO, Numeric(0.0)),
+        (&registers::TIME_ZONE, Numeric(60.0)),
+        (&registers::STORAGE_MAXIMUM_CHARGING_POWER, Numeric(5000.0)),
+        (&registers::STORAGE_MAXIMUM_DISCHARGING_POWER, Numeric(5000.0)),
+        (&registers::STORAGE_CHARGING_CUTOFF_CAPACITY, Numeric(100.0)),
+        (&registers::STORAGE_DISCHARGING_CUTOFF_CAPACITY, Numeric(5.0)),
+        (&registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_PERIOD, Numeric(0.0)),
+        (&registers::STORAGE_WORKING_MODE_SETTING, Numeric(2.0)),
+        (&registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_SOC, Numeric(100.0)),
+        (&registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_SETTING_MODE, Numeric(0.0)),
+        (&registers::STORAGE_FORCIBLE_CHARGE_POWER, Numeric(0.0)),
+        (&registers::STORAGE_FORCIBLE_DISCHARGE_POWER, Numeric(0.0)),
+        (&registers::ACTIVE_POWER_CONTROL_MODE, Numeric(0.0)),
+        (&registers::MAXIMUM_FEED_GRID_POWER, Numeric(0.0)),
+        (&registers::MAXIMUM_FEED_GRID_POWER_PERCENT, Numeric(100.0)),
+    ]
+}
@@ -0,0 +1,81 @@
+use crate::DEVICE_STATUS_DEFINITIONS;
+use std::fmt;
+
+// Device status grouped by category, which is the high byte of the status code. The full code
+// is kept so the exact sub-state (e.g. which kind of shutdown) stays available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "category", content = "code"))]
+pub enum DeviceStatus {
+    Standby(u16),
+    Starting(u16),
+    OnGrid(u16),
+    Shutdown(u16),
+    GridScheduling(u16),
+    SpotCheck(u16),
+    Inspecting(u16),
+    AfciSelfCheck(u16),
+    IvScanning(u16),
+    DcInputDetection(u16),
+    OffGrid(u16),
+    Unknown(u16)
+}
+
+const SHUTDOWN_FAULT: u16 = 0x0300;
+
+impl DeviceStatus {
+    pub fn from_code(code: u16) -> DeviceStatus {
+        match code >> 8 {
+            0x00 | 0xA0 => DeviceStatus::Standby(code),
+            0x01 => DeviceStatus::Starting(code),
+            0x02 => DeviceStatus::OnGrid(code),
+            0x03 => DeviceStatus::Shutdown(code),
+            0x04 => DeviceStatus::GridScheduling(code),
+            0x05 => DeviceStatus::SpotCheck(code),
+            0x06 => DeviceStatus::Inspecting(code),
+            0x07 => DeviceStatus::AfciSelfCheck(code),
+            0x08 => DeviceStatus::IvScanning(code),
+            0x09 => DeviceStatus::DcInputDetection(code),
+            0x0A => DeviceStatus::OffGrid(code),
+            _ => DeviceStatus::Unknown(code)
+        }
+    }
+
+    pub fn code(&self) -> u16 {
+        match *self {
+            DeviceStatus::Standby(code) | DeviceStatus::Starting(code) | DeviceStatus::OnGrid(code)
+                | DeviceStatus::Shutdown(code) | DeviceStatus::GridScheduling(code) | DeviceStatus::SpotCheck(code)
+                | DeviceStatus::Inspecting(code) | DeviceStatus::AfciSelfCheck(code) | DeviceStatus::IvScanning(code)
+                | DeviceStatus::DcInputDetection(code) | DeviceStatus::OffGrid(code) | DeviceStatus::Unknown(code) => code
+        }
+    }
+
+    // Text from `DEVICE_STATUS_DEFINITIONS`, if the exact code is listed there.
+    pub fn description(&self) -> Option<&'static str> {
+        DEVICE_STATUS_DEFINITIONS.iter().find(|(code, _)| *code == self.code()).map(|(_, desc)| *desc)
+    }
+
+    // Whether the inverter is feeding power, to the grid or to off-grid loads.
+    pub fn is_producing(&self) -> bool {
+        matches!(self, DeviceStatus::OnGrid(_) | DeviceStatus::GridScheduling(_) | DeviceStatus::OffGrid(_))
+    }
+
+    pub fn is_fault(&self) -> bool {
+        self.code() == SHUTDOWN_FAULT
+    }
+}
+
+impl From<u16> for DeviceStatus {
+    fn from(code: u16) -> DeviceStatus {
+        DeviceStatus::from_code(code)
+    }
+}
+
+impl fmt::Display for DeviceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.description() {
+            Some(desc) => write!(f, "{}", desc),
+            None => write!(f, "Unknown status 0x{:04X}", self.code())
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{pdu, ConnectionOptions, Error, RegisterTransport};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::thread;
+
+// Modbus TCP (MBAP framing) over a blocking socket.
+pub struct TcpTransport {
+    stream: TcpStream,
+    tid: u16,
+    slave_id: u8
+}
+
+impl TcpTransport {
+    pub fn connect(ip: &str, port: u16, slave_id: u8, options: &ConnectionOptions) -> Result<TcpTransport, Error> {
+        Ok(TcpTransport::new(open_stream(ip, port, options)?, slave_id))
+    }
+
+    pub fn new(stream: TcpStream, slave_id: u8) -> TcpTransport {
+        TcpTransport { stream, tid: 0, slave_id }
+    }
+
+    fn request(&mut self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        self.tid = self.tid.wrapping_add(1);
+        let mut frame = pdu::mbap_header(self.tid, self.slave_id, request.len()).to_vec();
+        frame.extend_from_slice(request);
+        self.stream.write_all(&frame)?;
+
+        // Skip late answers to earlier requests that timed out.
+        loop {
+            let mut header = [0u8; pdu::MBAP_HEADER_SIZE];
+            self.stream.read_exact(&mut header)?;
+            let (tid, len) = pdu::parse_mbap_header(&header)?;
+            let mut response = vec![0u8; len];
+            self.stream.read_exact(&mut response)?;
+            if tid == self.tid {
+                return Ok(response);
+            }
+        }
+    }
+}
+
+impl RegisterTransport for TcpTransport {
+    fn read_holding_registers(&mut self, addr: u16, count: u16) -> Result<Vec<u16>, Error> {
+        let response = self.request(&pdu::read_holding_registers_request(addr, count))?;
+        pdu::parse_read_holding_registers_response(&response, count)
+    }
+
+    fn write_single_register(&mut self, addr: u16, value: u16) -> Result<(), Error> {
+        let request = pdu::write_single_register_request(addr, value);
+        let response = self.request(&request)?;
+        pdu::check_write_response(&request, &response)
+    }
+
+    fn write_multiple_registers(&mut self, addr: u16, values: &[u16]) -> Result<(), Error> {
+        let request = pdu::write_multiple_registers_request(addr, values);
+        let response = self.request(&request)?;
+        pdu::check_write_response(&request, &response)
+    }
+
+    fn raw_request(&mut self, pdu: &[u8]) -> Result<Vec<u8>, Error> {
+        self.request(pdu)
+    }
+}
+
+// Connects and applies the socket related options, then waits for the settle delay.
+pub fn open_stream(ip: &str, port: u16, options: &ConnectionOptions) -> Result<TcpStream, Error> {
+    let stream = match options.connect_timeout {
+        Some(timeout) => {
+            let mut result = Err(Error::Connection(std::io::ErrorKind::NotFound.into()));
+            for addr in (ip, port).to_socket_addrs()? {
+                result = TcpStream::connect_timeout(&addr, timeout).map_err(Error::from);
+                if result.is_ok() {
+                    break;
+                }
+            }
+            result?
+        },
+        None => TcpStream::connect((ip, port))?
+    };
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(options.read_timeout)?;
+    stream.set_write_timeout(options.write_timeout)?;
+    if let Some(idle) = options.keepalive {
+        socket2::SockRef::from(&stream).set_tcp_keepalive(&socket2::TcpKeepalive::new().with_time(idle))?;
+    }
+    thread::sleep(options.settle_delay);
+    Ok(stream)
+}
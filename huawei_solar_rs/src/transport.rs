@@ -0,0 +1,34 @@
+use crate::Error;
+use std::io;
+
+// Backend used by `HuaweiSolar` to talk to the inverter. Implement it to run the library
+// over another medium (RTU, a proxy, a recorder or a mock).
+pub trait RegisterTransport {
+    fn read_holding_registers(&mut self, addr: u16, count: u16) -> Result<Vec<u16>, Error>;
+    fn write_single_register(&mut self, addr: u16, value: u16) -> Result<(), Error>;
+    fn write_multiple_registers(&mut self, addr: u16, values: &[u16]) -> Result<(), Error>;
+
+    // Sends a request PDU (function code and data) and returns the response PDU, which is
+    // needed for Huawei's private functions such as the file upload.
+    fn raw_request(&mut self, _pdu: &[u8]) -> Result<Vec<u8>, Error> {
+        Err(Error::Connection(io::Error::new(io::ErrorKind::Unsupported, "raw requests are not supported by this transport")))
+    }
+}
+
+impl<T: RegisterTransport + ?Sized> RegisterTransport for Box<T> {
+    fn read_holding_registers(&mut self, addr: u16, count: u16) -> Result<Vec<u16>, Error> {
+        (**self).read_holding_registers(addr, count)
+    }
+
+    fn write_single_register(&mut self, addr: u16, value: u16) -> Result<(), Error> {
+        (**self).write_single_register(addr, value)
+    }
+
+    fn write_multiple_registers(&mut self, addr: u16, values: &[u16]) -> Result<(), Error> {
+        (**self).write_multiple_registers(addr, values)
+    }
+
+    fn raw_request(&mut self, pdu: &[u8]) -> Result<Vec<u8>, Error> {
+        (**self).raw_request(pdu)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{pdu, Error, HuaweiSolar, RegisterTransport};
+
+const START_UPLOAD: u8 = 0x05;
+const UPLOAD_DATA: u8 = 0x06;
+const COMPLETE_UPLOAD: u8 = 0x0C;
+// The optimizer data file of an inverter with every optimizer connected stays far below this,
+// so a larger length comes from a corrupt response.
+const MAX_FILE_LENGTH: usize = 64 * 1024;
+
+impl<C: RegisterTransport> HuaweiSolar<C> {
+    // Uploads a file from the device: the start request announces the file length, then
+    // numbered data frames are fetched until it is complete, and the completion response
+    // carries a CRC over the whole file.
+    pub fn read_file(&mut self, file_type: u8, customized_data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut content = vec![file_type];
+        content.extend_from_slice(customized_data);
+        let start = self.file_request(START_UPLOAD, &content)?;
+        if start.len() < 6 || start[0] != file_type {
+            return Err(Error::InvalidResponse("invalid response to file upload start".to_string()));
+        }
+        let length = u32::from_be_bytes([start[1], start[2], start[3], start[4]]) as usize;
+        if length > MAX_FILE_LENGTH {
+            return Err(Error::InvalidResponse(format!("file length {} exceeds {} bytes", length, MAX_FILE_LENGTH)));
+        }
+
+        let mut data = Vec::new();
+        let mut frame: u16 = 0;
+        while data.len() < length {
+            let number = frame.to_be_bytes();
+            let response = self.file_request(UPLOAD_DATA, &[file_type, number[0], number[1]])?;
+            if response.len() <= 3 || response[..3] != [file_type, number[0], number[1]] {
+                return Err(Error::InvalidResponse(format!("invalid response to file data frame {}", frame)));
+            }
+            data.extend_from_slice(&response[3..]);
+            if data.len() > length {
+                return Err(Error::InvalidResponse(format!("file data exceeds the announced length of {} bytes", length)));
+            }
+            frame = frame.checked_add(1).ok_or_else(|| Error::InvalidResponse("file has too many data frames".to_string()))?;
+        }
+
+        let complete = self.file_request(COMPLETE_UPLOAD, &[file_type])?;
+        if complete.len() < 3 || complete[0] != file_type {
+            return Err(Error::InvalidResponse("invalid response to file upload completion".to_string()));
+        }
+        let expected = u16::from_be_bytes([complete[1], complete[2]]);
+        let actual = pdu::crc16(&data);
+        if expected != actual {
+            return Err(Error::CrcMismatch { expected, actual });
+        }
+        Ok(data)
+    }
+
+    fn file_request(&mut self, sub_function: u8, content: &[u8]) -> Result<Vec<u8>, Error> {
+        let request = pdu::huawei_file_request(sub_function, content);
+        self.request(|client| {
+            let response = client.raw_request(&request)?;
+            pdu::parse_huawei_file_response(sub_function, &response).map(<[u8]>::to_vec)
+        })
+    }
+}
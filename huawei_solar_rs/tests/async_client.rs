@@ -0,0 +1,87 @@
+#![cfg(feature = "tokio")]
+
+use huawei_solar_rs::simulator::{Simulator, SimulatorHandle};
+use huawei_solar_rs::{registers, AsyncHuaweiSolar, ConnectionOptions, DeviceStatus, Error, ExceptionCode, Register, RegisterValue};
+use std::time::{Duration, Instant};
+
+fn start(simulator: Simulator) -> SimulatorHandle {
+    simulator.start("127.0.0.1:0").unwrap()
+}
+
+async fn connect_with_options(sim: &SimulatorHandle, options: ConnectionOptions) -> AsyncHuaweiSolar {
+    AsyncHuaweiSolar::new_connection_with_options("127.0.0.1", sim.local_addr().port(), 0, options).await.unwrap()
+}
+
+async fn connect(sim: &SimulatorHandle) -> AsyncHuaweiSolar {
+    connect_with_options(sim, ConnectionOptions::new().settle_delay(Duration::ZERO)).await
+}
+
+#[tokio::test]
+async fn reads_registers() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim).await;
+    assert_eq!(inverter.read_string_register(&registers::MODEL_NAME).await.unwrap(), "SUN2000-5KTL-M1");
+    assert_eq!(inverter.read_numeric_register(&registers::ACTIVE_POWER).await.unwrap(), 4980.0);
+    assert_eq!(inverter.read_device_status(&registers::DEVICE_STATUS).await.unwrap(), DeviceStatus::OnGrid(0x0200));
+}
+
+#[tokio::test]
+async fn reads_many_with_fallback() {
+    let mut simulator = Simulator::new();
+    simulator.inject_exception(registers::GRID_FREQUENCY.addr(), ExceptionCode::IllegalDataAddress);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim).await;
+    let regs: [&dyn Register; 3] = [&registers::POWER_FACTOR, &registers::GRID_FREQUENCY, &registers::EFFICIENCY];
+    let values = inverter.read_many(&regs).await;
+    assert!(matches!(values[0], Ok(RegisterValue::Numeric(value)) if value == 0.999));
+    assert!(matches!(values[1], Err(Error::Exception(ExceptionCode::IllegalDataAddress))));
+    assert!(matches!(values[2], Ok(RegisterValue::Numeric(value)) if value == 98.2));
+}
+
+#[tokio::test]
+async fn waits_for_settle_delay() {
+    let sim = start(Simulator::new());
+    let started = Instant::now();
+    connect_with_options(&sim, ConnectionOptions::new().settle_delay(Duration::from_millis(200))).await;
+    assert!(started.elapsed() >= Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn times_out_slow_responses() {
+    let mut simulator = Simulator::new();
+    simulator.latency(Duration::from_millis(500));
+    let sim = start(simulator);
+    let options = ConnectionOptions::new().settle_delay(Duration::ZERO).read_timeout(Duration::from_millis(100));
+    let mut inverter = connect_with_options(&sim, options).await;
+    match inverter.read_numeric_register(&registers::ACTIVE_POWER).await {
+        Err(Error::Timeout) => {},
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+#[tokio::test]
+async fn skips_response_of_cancelled_request() {
+    let mut simulator = Simulator::new();
+    simulator.latency(Duration::from_millis(200));
+    let sim = start(simulator);
+    let mut inverter = connect(&sim).await;
+    let cancelled = tokio::time::timeout(Duration::from_millis(50), inverter.read_numeric_register(&registers::ACTIVE_POWER)).await;
+    assert!(cancelled.is_err());
+    assert_eq!(inverter.read_numeric_register(&registers::GRID_FREQUENCY).await.unwrap(), 50.01);
+}
+
+#[tokio::test]
+async fn fails_fast_until_reconnected() {
+    let mut simulator = Simulator::new();
+    simulator.drop_connections_after(1);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim).await;
+    inverter.read_numeric_register(&registers::ACTIVE_POWER).await.unwrap();
+    assert!(matches!(inverter.read_numeric_register(&registers::ACTIVE_POWER).await, Err(Error::Connection(_))));
+    match inverter.read_numeric_register(&registers::ACTIVE_POWER).await {
+        Err(Error::Connection(e)) => assert_eq!(e.kind(), std::io::ErrorKind::NotConnected),
+        other => panic!("unexpected result: {:?}", other)
+    }
+    inverter.reconnect().await.unwrap();
+    assert_eq!(inverter.read_numeric_register(&registers::ACTIVE_POWER).await.unwrap(), 4980.0);
+}
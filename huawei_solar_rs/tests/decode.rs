@@ -0,0 +1,83 @@
+use huawei_solar_rs::{Error, NumericRegister, NumericRegisterTrait, Register, RegisterValue};
+
+fn decode<T>(reg: &NumericRegister<T>, words: &[u16]) -> Result<f64, Error> where NumericRegister<T>: NumericRegisterTrait {
+    NumericRegisterTrait::decode(reg, words).map(|values| values[0])
+}
+
+fn assert_unavailable(result: Result<f64, Error>) {
+    match result {
+        Err(Error::ValueUnavailable { addr: 100 }) => {},
+        other => panic!("expected an unavailable value, got {:?}", other)
+    }
+}
+
+#[test]
+fn decodes_u16() {
+    let reg: NumericRegister<u16> = NumericRegister::new(100, 1, 10, "V");
+    assert_eq!(decode(&reg, &[0]).unwrap(), 0.0);
+    assert_eq!(decode(&reg, &[2305]).unwrap(), 230.5);
+    assert_eq!(decode(&reg, &[0x8000]).unwrap(), 3276.8);
+    assert_eq!(decode(&reg, &[0xFFFE]).unwrap(), 6553.4);
+    assert_unavailable(decode(&reg, &[0xFFFF]));
+}
+
+#[test]
+fn decodes_i16() {
+    let reg: NumericRegister<i16> = NumericRegister::new(100, 1, 10, "°C");
+    assert_eq!(decode(&reg, &[0]).unwrap(), 0.0);
+    assert_eq!(decode(&reg, &[413]).unwrap(), 41.3);
+    assert_eq!(decode(&reg, &[0xFFFF]).unwrap(), -0.1);
+    assert_eq!(decode(&reg, &[(-125i16) as u16]).unwrap(), -12.5);
+    assert_eq!(decode(&reg, &[0x8000]).unwrap(), -3276.8);
+    assert_eq!(decode(&reg, &[0x7FFE]).unwrap(), 3276.6);
+    assert_unavailable(decode(&reg, &[0x7FFF]));
+}
+
+#[test]
+fn decodes_u32() {
+    let reg: NumericRegister<u32> = NumericRegister::new(100, 2, 100, "kWh");
+    assert_eq!(decode(&reg, &[0, 0]).unwrap(), 0.0);
+    assert_eq!(decode(&reg, &[0x0012, 0xD687]).unwrap(), 12345.67);
+    assert_eq!(decode(&reg, &[0x8000, 0x0000]).unwrap(), 21474836.48);
+    assert_eq!(decode(&reg, &[0xFFFF, 0xFFFE]).unwrap(), 42949672.94);
+    assert_unavailable(decode(&reg, &[0xFFFF, 0xFFFF]));
+}
+
+#[test]
+fn decodes_i32() {
+    let reg: NumericRegister<i32> = NumericRegister::new(100, 2, 1, "W");
+    assert_eq!(decode(&reg, &[0, 0]).unwrap(), 0.0);
+    assert_eq!(decode(&reg, &[0x0000, 0x1374]).unwrap(), 4980.0);
+    assert_eq!(decode(&reg, &[0xFFFF, 0xFFFF]).unwrap(), -1.0);
+    assert_eq!(decode(&reg, &[0xFFFF, 0xEC78]).unwrap(), -5000.0);
+    assert_eq!(decode(&reg, &[0x8000, 0x0000]).unwrap(), i32::MIN as f64);
+    assert_eq!(decode(&reg, &[0x7FFF, 0xFFFE]).unwrap(), (i32::MAX - 1) as f64);
+    assert_unavailable(decode(&reg, &[0x7FFF, 0xFFFF]));
+}
+
+#[test]
+fn rejects_short_responses() {
+    let reg: NumericRegister<u32> = NumericRegister::new(100, 2, 1, "W");
+    match decode(&reg, &[0x0001]) {
+        Err(Error::ShortResponse { expected: 2, actual: 1 }) => {},
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+#[test]
+fn encodes_signed_values_as_twos_complement() {
+    let power_factor: NumericRegister<i16> = NumericRegister::new(100, 1, 1000, "");
+    assert_eq!(NumericRegisterTrait::encode(&power_factor, -0.95), vec![(-950i16) as u16]);
+    let power: NumericRegister<i32> = NumericRegister::new(100, 2, 1, "W");
+    assert_eq!(NumericRegisterTrait::encode(&power, -5000.0), vec![0xFFFF, 0xEC78]);
+}
+
+#[test]
+fn round_trips_through_register_values() {
+    let temperature: NumericRegister<i16> = NumericRegister::new(100, 1, 10, "°C");
+    let words = Register::encode(&temperature, &RegisterValue::Numeric(-7.5)).unwrap();
+    match Register::decode(&temperature, &words).unwrap() {
+        RegisterValue::Numeric(value) => assert_eq!(value, -7.5),
+        RegisterValue::String(value) => panic!("expected a number, got {:?}", value)
+    }
+}
@@ -0,0 +1,130 @@
+use huawei_solar_rs::{parse_optimizer_data, Error, HuaweiSolar, OptimizerReading, RegisterTransport};
+use std::collections::VecDeque;
+
+// Replays a recorded exchange, failing on any request that differs from the recording.
+struct Recording {
+    exchanges: VecDeque<(Vec<u8>, Vec<u8>)>
+}
+
+impl RegisterTransport for Recording {
+    fn read_holding_registers(&mut self, _addr: u16, _count: u16) -> Result<Vec<u16>, Error> {
+        panic!("unexpected register read");
+    }
+
+    fn write_single_register(&mut self, _addr: u16, _value: u16) -> Result<(), Error> {
+        panic!("unexpected register write");
+    }
+
+    fn write_multiple_registers(&mut self, _addr: u16, _values: &[u16]) -> Result<(), Error> {
+        panic!("unexpected register write");
+    }
+
+    fn raw_request(&mut self, pdu: &[u8]) -> Result<Vec<u8>, Error> {
+        let (request, response) = self.exchanges.pop_front().expect("no more recorded exchanges");
+        assert_eq!(pdu, &request[..]);
+        Ok(response)
+    }
+}
+
+// Optimizer real-time data file of one data unit with two optimizers, sent in 32-byte frames.
+const FRAME_0: [u8; 32] = [
+    0x00, 0x00, 0x00, 0x01, 0x68, 0xE7, 0x78, 0x00, 0x00, 0x02, 0x00, 0x01, 0x0C, 0x8F, 0xFB, 0x4D,
+    0x00, 0x00, 0x00, 0x00, 0x01, 0x9C, 0x03, 0x0C, 0x01, 0x81, 0x03, 0x43, 0x01, 0x60, 0x00, 0x02
+];
+const FRAME_1: [u8; 30] = [
+    0x00, 0x12, 0xD6, 0x87, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xC9, 0x00, 0x00, 0x00, 0x00, 0x03, 0x7A
+];
+const FILE_CRC: u16 = 0xCFC1;
+
+fn optimizer_upload(crc: u16) -> Recording {
+    optimizer_upload_with_length(62, crc)
+}
+
+fn optimizer_upload_with_length(length: u32, crc: u16) -> Recording {
+    let mut data_0 = vec![0x41, 0x06, 35, 0x44, 0x00, 0x00];
+    data_0.extend_from_slice(&FRAME_0);
+    let mut data_1 = vec![0x41, 0x06, 33, 0x44, 0x00, 0x01];
+    data_1.extend_from_slice(&FRAME_1);
+    let crc = crc.to_be_bytes();
+    let exchanges = vec![
+        (vec![0x41, 0x05, 0x01, 0x44], start_response(length)),
+        (vec![0x41, 0x06, 0x03, 0x44, 0x00, 0x00], data_0),
+        (vec![0x41, 0x06, 0x03, 0x44, 0x00, 0x01], data_1),
+        (vec![0x41, 0x0C, 0x01, 0x44], vec![0x41, 0x0C, 0x03, 0x44, crc[0], crc[1]])
+    ];
+    Recording { exchanges: exchanges.into() }
+}
+
+fn start_response(length: u32) -> Vec<u8> {
+    let mut response = vec![0x41, 0x05, 0x06, 0x44];
+    response.extend_from_slice(&length.to_be_bytes());
+    response.push(32);
+    response
+}
+
+#[test]
+fn reads_optimizer_data_file() {
+    let mut inverter = HuaweiSolar::new(optimizer_upload(FILE_CRC));
+    let readings = inverter.read_optimizer_data().unwrap();
+    assert_eq!(readings, vec![
+        OptimizerReading {
+            time: 1760000000,
+            position: 1,
+            running_status: 2,
+            alarm: 0,
+            output_power: 321.5,
+            output_voltage: 41.2,
+            output_current: 7.8,
+            input_voltage: 38.5,
+            input_current: 8.35,
+            voltage_to_ground: -120.3,
+            temperature: 35.2,
+            total_energy: 1234.567
+        },
+        OptimizerReading {
+            time: 1760000000,
+            position: 2,
+            running_status: 0,
+            alarm: 0x10,
+            output_power: 0.0,
+            output_voltage: 0.0,
+            output_current: 0.0,
+            input_voltage: 0.0,
+            input_current: 0.0,
+            voltage_to_ground: 0.0,
+            temperature: -5.5,
+            total_energy: 0.89
+        }
+    ]);
+}
+
+#[test]
+fn rejects_file_with_wrong_crc() {
+    let mut inverter = HuaweiSolar::new(optimizer_upload(0x1234));
+    match inverter.read_file(0x44, &[]) {
+        Err(Error::CrcMismatch { expected: 0x1234, actual: FILE_CRC }) => {},
+        other => panic!("expected CRC mismatch, got {:?}", other)
+    }
+}
+
+#[test]
+fn rejects_truncated_optimizer_data() {
+    let mut file = FRAME_0.to_vec();
+    file.extend_from_slice(&FRAME_1[..20]);
+    assert!(matches!(parse_optimizer_data(&file), Err(Error::InvalidResponse(_))));
+    assert_eq!(parse_optimizer_data(&FRAME_0[..4]).unwrap(), vec![]);
+}
+
+#[test]
+fn rejects_oversized_file_length() {
+    let exchanges = vec![(vec![0x41, 0x05, 0x01, 0x44], start_response(0xFFFF_FFFF))];
+    let mut inverter = HuaweiSolar::new(Recording { exchanges: exchanges.into() });
+    assert!(matches!(inverter.read_file(0x44, &[]), Err(Error::InvalidResponse(_))));
+}
+
+#[test]
+fn rejects_data_beyond_file_length() {
+    let mut inverter = HuaweiSolar::new(optimizer_upload_with_length(40, FILE_CRC));
+    assert!(matches!(inverter.read_file(0x44, &[]), Err(Error::InvalidResponse(_))));
+}
@@ -0,0 +1,100 @@
+#![cfg(all(unix, feature = "serial"))]
+
+mod common;
+
+use common::crc16;
+use huawei_solar_rs::{registers, Error, ExceptionCode, HuaweiSolar, RtuTransport};
+use serialport::{SerialPort, TTYPort};
+use std::io::{Read, Write};
+use std::thread;
+use std::time::Duration;
+
+const SLAVE_ID: u8 = 1;
+
+fn with_crc(mut frame: Vec<u8>) -> Vec<u8> {
+    let crc = crc16(&frame);
+    frame.extend_from_slice(&crc.to_le_bytes());
+    frame
+}
+
+fn register_value(addr: u16) -> u16 {
+    match addr {
+        // "SUN2000" padded with zeros
+        30000..=30003 => [0x5355, 0x4E32, 0x3030, 0x3000][(addr - 30000) as usize],
+        32080 => 0x0000,
+        32081 => 0x0BB8,
+        _ => 0
+    }
+}
+
+// Plays the inverter on the master side of the pty pair. `respond` builds the reply frame
+// for each read request. The master is handed back so it stays open until the test joins,
+// otherwise the slave side sees a hang-up before reading the last reply.
+fn fake_inverter(mut master: TTYPort, requests: usize, respond: fn(u16, u16) -> Vec<u8>) -> thread::JoinHandle<TTYPort> {
+    master.set_timeout(Duration::from_secs(2)).unwrap();
+    thread::spawn(move || {
+        for _ in 0..requests {
+            let mut request = [0u8; 8];
+            master.read_exact(&mut request).unwrap();
+            assert_eq!(request[0], SLAVE_ID);
+            assert_eq!(request[1], 0x03);
+            assert_eq!(crc16(&request[..6]).to_le_bytes(), [request[6], request[7]]);
+            let addr = u16::from_be_bytes([request[2], request[3]]);
+            let count = u16::from_be_bytes([request[4], request[5]]);
+            master.write_all(&respond(addr, count)).unwrap();
+        }
+        master
+    })
+}
+
+fn registers_response(addr: u16, count: u16) -> Vec<u8> {
+    let mut frame = vec![SLAVE_ID, 0x03, (count * 2) as u8];
+    for i in 0..count {
+        frame.extend_from_slice(&register_value(addr + i).to_be_bytes());
+    }
+    with_crc(frame)
+}
+
+fn connect() -> (TTYPort, HuaweiSolar<RtuTransport<TTYPort>>) {
+    let (master, mut slave) = TTYPort::pair().expect("unable to create pty pair");
+    slave.set_timeout(Duration::from_secs(2)).unwrap();
+    (master, HuaweiSolar::new(RtuTransport::new(slave, SLAVE_ID)))
+}
+
+#[test]
+fn reads_numeric_and_string_registers() {
+    let (master, mut inverter) = connect();
+    let server = fake_inverter(master, 2, registers_response);
+    assert_eq!(inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap(), 3000.0);
+    assert_eq!(inverter.read_string_register(&registers::MODEL_NAME).unwrap(), "SUN2000");
+    server.join().unwrap();
+}
+
+#[test]
+fn reports_exception_responses() {
+    let (master, mut inverter) = connect();
+    let server = fake_inverter(master, 1, |_, _| with_crc(vec![SLAVE_ID, 0x83, 0x02]));
+    match inverter.read_numeric_register(&registers::ACTIVE_POWER) {
+        Err(Error::Exception(ExceptionCode::IllegalDataAddress)) => {},
+        other => panic!("unexpected result: {:?}", other)
+    }
+    server.join().unwrap();
+}
+
+#[test]
+fn rejects_frames_with_bad_crc() {
+    let (master, mut inverter) = connect();
+    // A CRC mismatch is transient, so the read is repeated once before failing.
+    let server = fake_inverter(master, 2, |addr, count| {
+        let mut frame = registers_response(addr, count);
+        let last = frame.len() - 1;
+        frame[last] ^= 0xFF;
+        frame
+    });
+    match inverter.read_numeric_register(&registers::ACTIVE_POWER) {
+        Err(Error::CrcMismatch { .. }) => {},
+        other => panic!("unexpected result: {:?}", other)
+    }
+    assert_eq!(inverter.stats().retries, 1);
+    server.join().unwrap();
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::crc16;
+use huawei_solar_rs::{registers, ConnectionOptions, Error, HuaweiSolar, RtuTransport};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+const SLAVE_ID: u8 = 1;
+
+fn registers_response(count: u16, corrupt: bool) -> Vec<u8> {
+    let mut frame = vec![SLAVE_ID, 0x03, (count * 2) as u8];
+    for i in 0..count {
+        frame.extend_from_slice(&(0x0BB8 + i).to_be_bytes());
+    }
+    let crc = crc16(&frame);
+    frame.extend_from_slice(&crc.to_le_bytes());
+    if corrupt {
+        let last = frame.len() - 1;
+        frame[last] ^= 0xFF;
+    }
+    frame
+}
+
+// Plays a transparent serial-to-Ethernet converter: RTU frames over a plain TCP socket. Each
+// entry of `replies` answers one read request and says whether its CRC is corrupted. A
+// corrupted reply ends the connection, as the client drops it anyway.
+fn fake_converter(replies: Vec<bool>) -> (u16, thread::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let mut replies = replies.into_iter().peekable();
+        while replies.peek().is_some() {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+            for corrupt in replies.by_ref() {
+                let mut request = [0u8; 8];
+                stream.read_exact(&mut request).unwrap();
+                assert_eq!(request[..2], [SLAVE_ID, 0x03]);
+                assert_eq!(crc16(&request[..6]).to_le_bytes(), [request[6], request[7]]);
+                let count = u16::from_be_bytes([request[4], request[5]]);
+                stream.write_all(&registers_response(count, corrupt)).unwrap();
+                if corrupt {
+                    break;
+                }
+            }
+        }
+    });
+    (port, server)
+}
+
+fn connect(port: u16, options: ConnectionOptions) -> HuaweiSolar<RtuTransport<TcpStream>> {
+    let options = options.settle_delay(Duration::ZERO).read_timeout(Duration::from_secs(2));
+    HuaweiSolar::new_rtu_over_tcp_connection_with_options("127.0.0.1", port, SLAVE_ID, options).unwrap()
+}
+
+#[test]
+fn reads_registers() {
+    let (port, server) = fake_converter(vec![false, false]);
+    let mut inverter = connect(port, ConnectionOptions::new());
+    assert_eq!(inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap(), 0x0BB8_0BB9 as f64);
+    assert_eq!(inverter.read_numeric_register(&registers::DEVICE_STATUS).unwrap(), 0x0BB8 as f64);
+    server.join().unwrap();
+}
+
+#[test]
+fn rejects_frames_with_bad_crc_and_reconnects() {
+    let (port, server) = fake_converter(vec![true, false]);
+    let mut inverter = connect(port, ConnectionOptions::new().retries(0));
+    match inverter.read_numeric_register(&registers::DEVICE_STATUS) {
+        Err(Error::CrcMismatch { .. }) => {},
+        other => panic!("unexpected result: {:?}", other)
+    }
+    assert_eq!(inverter.read_numeric_register(&registers::DEVICE_STATUS).unwrap(), 0x0BB8 as f64);
+    assert_eq!(inverter.stats().reconnects, 1);
+    server.join().unwrap();
+}
+
+#[test]
+fn retries_bad_crc_on_new_connection() {
+    let (port, server) = fake_converter(vec![true, false]);
+    let mut inverter = connect(port, ConnectionOptions::new().retry_backoff(Duration::ZERO));
+    assert_eq!(inverter.read_numeric_register(&registers::DEVICE_STATUS).unwrap(), 0x0BB8 as f64);
+    assert_eq!(inverter.stats().reconnects, 1);
+    server.join().unwrap();
+}
@@ -0,0 +1,607 @@
+use chrono::{DateTime, FixedOffset, TimeZone};
+use huawei_solar_rs::simulator::{Simulator, SimulatorHandle};
+use huawei_solar_rs::{registers, ActivePowerLimit, AlarmSeverity, BatteryLimits, BatteryRunningStatus, BatteryWorkingMode, BatteryWorkingModeSetting, ConnectionOptions, DeviceStatus, MeterPhase, MeterType, Error, ExceptionCode, ExportLimit, ForcibleCommand, ForcibleTarget, HuaweiSolar, NumericRegister, PowerLimit, PvString, ReactivePowerControl, ReactivePowerSetpoints, Register, RegisterValue, WritableRegister};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn start(simulator: Simulator) -> SimulatorHandle {
+    simulator.start("127.0.0.1:0").unwrap()
+}
+
+fn connect(sim: &SimulatorHandle, slave_id: u8) -> HuaweiSolar {
+    connect_with_options(sim, slave_id, ConnectionOptions::new())
+}
+
+fn connect_with_options(sim: &SimulatorHandle, slave_id: u8, options: ConnectionOptions) -> HuaweiSolar {
+    let options = options.settle_delay(Duration::ZERO);
+    HuaweiSolar::new_connection_with_options("127.0.0.1", sim.local_addr().port(), slave_id, options).unwrap()
+}
+
+#[test]
+fn serves_default_register_map() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    assert_eq!(inverter.read_string_register(&registers::MODEL_NAME).unwrap(), "SUN2000-5KTL-M1");
+    assert_eq!(inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap(), 4980.0);
+    assert_eq!(inverter.read_numeric_register(&registers::GRID_FREQUENCY).unwrap(), 50.01);
+    assert_eq!(inverter.read_numeric_register(&registers::ACCUMULATED_YIELD_ENERGY).unwrap(), 12345.67);
+    let status = inverter.read_device_status(&registers::DEVICE_STATUS).unwrap();
+    assert_eq!(status, DeviceStatus::OnGrid(0x0200));
+    assert_eq!(status.to_string(), "On-grid");
+}
+
+#[test]
+fn reads_device_info() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    let info = inverter.read_device_info().unwrap();
+    assert_eq!(info.model_name, "SUN2000-5KTL-M1");
+    assert_eq!(info.serial_number, "SIM0000001");
+    assert_eq!(info.product_number, "01074425");
+    assert_eq!(info.software_version, "V100R001C00SPC133");
+    assert_eq!(info.protocol_version, 0x0003_0001);
+    assert_eq!((info.model_id, info.number_of_pv_strings, info.number_of_mpp_trackers), (428, 2, 2));
+    assert_eq!((info.rated_power, info.max_active_power), (5000.0, 5500.0));
+    assert_eq!(info.max_reactive_power_from_grid, -3300.0);
+}
+
+#[test]
+fn read_many_matches_single_reads() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    let regs: [&dyn Register; 5] = [
+        &registers::DAILY_YIELD_ENERGY,
+        &registers::INPUT_POWER,
+        &registers::PHASE_B_CURRENT,
+        &registers::INTERNAL_TEMPERATURE,
+        &registers::STARTUP_TIME
+    ];
+    let values: Vec<RegisterValue> = inverter.read_many(&regs).into_iter().map(Result::unwrap).collect();
+    let expected = [23.45, 5120.0, 7.198, 41.3, 1_700_000_000.0].map(RegisterValue::Numeric);
+    assert_eq!(values, expected);
+}
+
+#[test]
+fn reads_pv_strings() {
+    let mut simulator = Simulator::new();
+    simulator.set_value(&registers::NUMBER_OF_PV_STRINGS, &RegisterValue::Numeric(3.0)).unwrap();
+    simulator.set_value(&registers::PV3_VOLTAGE, &RegisterValue::Numeric(12.5)).unwrap();
+    simulator.set_value(&registers::PV3_CURRENT, &RegisterValue::Numeric(-0.04)).unwrap();
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let strings = inverter.read_pv_strings().unwrap();
+    assert_eq!(strings.len(), 3);
+    assert_eq!(strings[0], PvString { index: 1, voltage: Some(361.4), current: Some(7.62), power: Some(2754.0) });
+    assert_eq!(strings[1].power, Some(2365.0));
+    assert_eq!((strings[2].index, strings[2].voltage, strings[2].current), (3, Some(12.5), Some(-0.04)));
+}
+
+#[test]
+fn reads_healthy_pv_strings_next_to_failed_ones() {
+    let mut simulator = Simulator::new();
+    simulator.inject_exception(registers::PV2_CURRENT.addr(), ExceptionCode::IllegalDataAddress);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let strings = inverter.read_pv_strings().unwrap();
+    assert_eq!(strings[0].power, Some(2754.0));
+    assert_eq!(strings[1], PvString { index: 2, voltage: Some(358.9), current: None, power: None });
+}
+
+#[test]
+fn reads_active_alarms() {
+    let mut simulator = Simulator::new();
+    simulator.set_words(registers::ALARM_1.addr(), &[0x0200, 0x0008, 0x4000]);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let alarms = inverter.read_alarms().unwrap();
+    let ids: Vec<u16> = alarms.iter().map(|alarm| alarm.id).collect();
+    assert_eq!(ids, vec![2034, 2063]);
+    assert_eq!(alarms[0].name, "Grid Overvoltage");
+    assert_eq!(alarms[1].severity, AlarmSeverity::Minor);
+}
+
+#[test]
+fn reads_fault_code() {
+    let mut simulator = Simulator::new();
+    simulator.set_value(&registers::FAULT_CODE, &RegisterValue::Numeric(2062.0)).unwrap();
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let fault = inverter.read_fault_code().unwrap();
+    assert_eq!(fault.code, 2062);
+    assert_eq!(fault.description, "Low insulation resistance");
+    assert!(!fault.suggested_action.is_empty());
+
+    sim.set_value(&registers::FAULT_CODE, &RegisterValue::Numeric(0.0)).unwrap();
+    assert_eq!(inverter.read_fault_code().unwrap().description, "No fault");
+}
+
+#[test]
+fn reads_battery() {
+    let mut simulator = Simulator::new();
+    simulator.set_value(&registers::STORAGE_CHARGE_DISCHARGE_POWER, &RegisterValue::Numeric(-820.0)).unwrap();
+    simulator.set_value(&registers::STORAGE_UNIT_1_PACK_2_SERIAL_NUMBER, &RegisterValue::String("SIMPACK002".to_string())).unwrap();
+    simulator.set_value(&registers::STORAGE_UNIT_1_PACK_2_MIN_TEMPERATURE, &RegisterValue::Numeric(-2.5)).unwrap();
+    simulator.inject_exception(registers::STORAGE_UNIT_2_SERIAL_NUMBER.addr(), ExceptionCode::IllegalDataAddress);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let battery = inverter.read_battery().unwrap();
+    assert_eq!(battery.state_of_charge, 64.5);
+    assert_eq!(battery.charge_discharge_power, -820.0);
+    assert_eq!(battery.running_status, BatteryRunningStatus::Running);
+    assert_eq!(battery.working_mode, BatteryWorkingMode::MaximiseSelfConsumption);
+    assert_eq!(battery.units.len(), 1);
+
+    let unit = &battery.units[0];
+    assert_eq!((unit.index, unit.serial_number.as_str(), unit.temperature), (1, "SIMBAT0001", 24.6));
+    let packs: Vec<(usize, &str)> = unit.packs.iter().map(|pack| (pack.index, pack.serial_number.as_str())).collect();
+    assert_eq!(packs, vec![(1, "SIMPACK001"), (2, "SIMPACK002")]);
+    assert_eq!(unit.packs[0].max_temperature, 25.1);
+    assert_eq!(unit.packs[1].min_temperature, -2.5);
+}
+
+#[test]
+fn fails_battery_read_on_device_failure() {
+    let mut simulator = Simulator::new();
+    simulator.inject_exception(registers::STORAGE_UNIT_1_SERIAL_NUMBER.addr(), ExceptionCode::SlaveDeviceFailure);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    assert!(matches!(inverter.read_battery(), Err(Error::Exception(ExceptionCode::SlaveDeviceFailure))));
+}
+
+#[test]
+fn reads_three_phase_power_meter() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    let meter = inverter.read_power_meter().unwrap();
+    assert!(meter.online);
+    assert_eq!(meter.meter_type, MeterType::ThreePhase);
+    assert_eq!(meter.phases.len(), 3);
+    assert_eq!(meter.phases[1], MeterPhase { voltage: 230.8, current: 3.98, active_power: 910.0 });
+    assert_eq!(meter.line_voltages, vec![400.2, 399.9, 401.1]);
+    assert_eq!(meter.reactive_power, -120.0);
+    assert_eq!(meter.exported_energy, 8210.55);
+}
+
+#[test]
+fn reads_single_phase_power_meter() {
+    let mut simulator = Simulator::new();
+    simulator.set_value(&registers::METER_TYPE, &RegisterValue::Numeric(0.0)).unwrap();
+    simulator.set_words(registers::METER_PHASE_B_VOLTAGE.addr(), &[0x7FFF, 0xFFFF]);
+    simulator.set_words(registers::METER_LINE_VOLTAGE_A_B.addr(), &[0x7FFF, 0xFFFF]);
+    simulator.set_value(&registers::METER_ACTIVE_POWER, &RegisterValue::Numeric(-450.0)).unwrap();
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let meter = inverter.read_power_meter().unwrap();
+    assert_eq!(meter.meter_type, MeterType::SinglePhase);
+    assert_eq!(meter.phases, vec![MeterPhase { voltage: 231.4, current: 4.12, active_power: -450.0 }]);
+    assert!(meter.line_voltages.is_empty());
+}
+
+#[test]
+fn reads_optimizer_summary() {
+    let mut simulator = Simulator::new();
+    simulator.set_value(&registers::NUMBER_OF_ONLINE_OPTIMIZERS, &RegisterValue::Numeric(10.0)).unwrap();
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let summary = inverter.read_optimizer_summary().unwrap();
+    assert_eq!((summary.total, summary.online, summary.offline()), (12, 10, 2));
+}
+
+#[test]
+fn reads_system_time() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    let time = inverter.read_system_time().unwrap();
+    assert_eq!(time.timestamp(), 1_700_000_000);
+    assert_eq!(time.offset().local_minus_utc(), 3600);
+    assert_eq!(time.to_rfc3339(), "2023-11-14T23:13:20+01:00");
+}
+
+#[test]
+fn sets_system_time() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    let time = DateTime::parse_from_rfc3339("2024-03-31T01:59:30-05:30").unwrap();
+    inverter.set_system_time(&time).unwrap();
+    assert_eq!(inverter.read_numeric_register(&registers::TIME_ZONE).unwrap(), -330.0);
+    assert_eq!(inverter.read_numeric_register(&registers::SYSTEM_TIME).unwrap(), (time.timestamp() - 330 * 60) as f64);
+    assert_eq!(inverter.read_system_time().unwrap(), time);
+
+    let odd_offset = FixedOffset::east_opt(30).unwrap().timestamp_opt(1_700_000_000, 0).unwrap();
+    assert!(matches!(inverter.set_system_time(&odd_offset), Err(Error::InvalidValue(_))));
+    let far_offset = FixedOffset::east_opt(-13 * 3600).unwrap().timestamp_opt(1_700_000_000, 0).unwrap();
+    assert!(matches!(inverter.set_system_time(&far_offset), Err(Error::InvalidValue(_))));
+}
+
+#[test]
+fn keeps_unchanged_time_zone() {
+    let mut simulator = Simulator::new();
+    // Marks writes to the time zone in an otherwise unused register.
+    simulator.on_write(registers::TIME_ZONE.addr(), 50000, &[1]);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let time = DateTime::parse_from_rfc3339("2024-03-31T01:59:30+01:00").unwrap();
+    inverter.set_system_time(&time).unwrap();
+    assert_eq!(inverter.read_system_time().unwrap(), time);
+    assert_eq!(sim.words(50000, 1), vec![0]);
+}
+
+#[test]
+fn reports_time_zone_changed_without_time() {
+    let mut simulator = Simulator::new();
+    simulator.inject_exception(registers::SYSTEM_TIME.addr(), ExceptionCode::SlaveDeviceFailure);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let time = DateTime::parse_from_rfc3339("2024-03-31T01:59:30+02:00").unwrap();
+    match inverter.set_system_time(&time) {
+        Err(Error::SystemTimeNotSet(e)) => assert!(matches!(*e, Error::Exception(ExceptionCode::SlaveDeviceFailure))),
+        other => panic!("unexpected result: {:?}", other)
+    }
+    assert_eq!(sim.words(registers::TIME_ZONE.addr(), 1), vec![120]);
+}
+
+#[test]
+fn writes_registers() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    let single = WritableRegister::new(NumericRegister::<i16>::new(40500, 1, 10, "%"), -100.0, 100.0);
+    let double = WritableRegister::new(NumericRegister::<u32>::new(40501, 2, 1000, "kW"), 0.0, 100.0);
+    inverter.write_register(&single, -12.5).unwrap();
+    inverter.write_register(&double, 70.125).unwrap();
+    assert_eq!(sim.words(40500, 3), vec![0xFF83, 0x0001, 0x11ED]);
+    assert_eq!(inverter.read_numeric_register(&single).unwrap(), -12.5);
+    assert_eq!(inverter.read_numeric_register(&double).unwrap(), 70.125);
+}
+
+#[test]
+fn rejects_writes_out_of_range() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    let reg = WritableRegister::new(NumericRegister::<u16>::new(40500, 1, 1, "W"), 0.0, 5000.0);
+    for value in [-1.0, 5000.5, f64::NAN] {
+        assert!(matches!(inverter.write_register(&reg, value), Err(Error::InvalidValue(_))));
+    }
+    assert_eq!(inverter.stats().requests, 0);
+}
+
+#[test]
+fn detects_writes_not_applied() {
+    let mut simulator = Simulator::new();
+    simulator.ignore_writes(40500);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let reg = WritableRegister::new(NumericRegister::<u16>::new(40500, 1, 1, "W"), 0.0, 5000.0);
+    match inverter.write_register(&reg, 1200.0) {
+        Err(Error::WriteNotApplied { addr: 40500, written, read }) => assert_eq!((written, read), (1200.0, 0.0)),
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+#[test]
+fn limits_active_power() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    assert_eq!(inverter.read_active_power_limit().unwrap(), ActivePowerLimit { percent: 100.0, watts: 5500.0, effective_watts: 5500.0 });
+
+    inverter.set_active_power_limit(PowerLimit::Percent(60.0)).unwrap();
+    assert_eq!(inverter.read_active_power_limit().unwrap().effective_watts, 3300.0);
+    inverter.set_active_power_limit(PowerLimit::Watts(2500.0)).unwrap();
+    assert_eq!(inverter.read_active_power_limit().unwrap(), ActivePowerLimit { percent: 60.0, watts: 2500.0, effective_watts: 2500.0 });
+    assert!(matches!(inverter.set_active_power_limit(PowerLimit::Percent(120.0)), Err(Error::InvalidValue(_))));
+
+    inverter.clear_active_power_limit().unwrap();
+    assert_eq!(inverter.read_active_power_limit().unwrap().effective_watts, 5500.0);
+}
+
+#[test]
+fn limits_grid_export() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    assert_eq!(inverter.read_export_limit().unwrap(), ExportLimit::Unlimited);
+    for limit in [ExportLimit::Watts(1500.0), ExportLimit::Percent(70.0), ExportLimit::Zero, ExportLimit::Unlimited] {
+        inverter.set_export_limit(limit).unwrap();
+        assert_eq!(inverter.read_export_limit().unwrap(), limit);
+    }
+    assert_eq!(inverter.read_numeric_register(&registers::MAXIMUM_FEED_GRID_POWER).unwrap(), 1500.0);
+}
+
+#[test]
+fn controls_reactive_power() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    assert_eq!(inverter.read_reactive_power_setpoints().unwrap(), ReactivePowerSetpoints { power_factor: 1.0, qs_ratio: 0.0 });
+    inverter.set_reactive_power_control(ReactivePowerControl::PowerFactor(-0.95)).unwrap();
+    inverter.set_reactive_power_control(ReactivePowerControl::QsRatio(0.25)).unwrap();
+    assert_eq!(inverter.read_reactive_power_setpoints().unwrap(), ReactivePowerSetpoints { power_factor: -0.95, qs_ratio: 0.25 });
+
+    for control in [ReactivePowerControl::PowerFactor(0.7), ReactivePowerControl::PowerFactor(1.1), ReactivePowerControl::QsRatio(-1.5)] {
+        assert!(matches!(inverter.set_reactive_power_control(control), Err(Error::InvalidValue(_))));
+    }
+    assert_eq!(inverter.read_reactive_power_setpoints().unwrap(), ReactivePowerSetpoints { power_factor: -0.95, qs_ratio: 0.25 });
+}
+
+#[test]
+fn controls_battery() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    assert_eq!(inverter.read_battery_working_mode_setting().unwrap(), BatteryWorkingModeSetting::MaximiseSelfConsumption);
+    inverter.set_battery_working_mode(BatteryWorkingModeSetting::TimeOfUseLuna2000).unwrap();
+    assert_eq!(inverter.read_battery_working_mode_setting().unwrap(), BatteryWorkingModeSetting::TimeOfUseLuna2000);
+
+    let mut limits = inverter.read_battery_limits().unwrap();
+    assert_eq!(limits, BatteryLimits { max_charge_power: 5000.0, max_discharge_power: 5000.0, charge_cutoff_soc: 100.0, discharge_cutoff_soc: 5.0 });
+    limits.max_charge_power = 2500.0;
+    limits.discharge_cutoff_soc = 15.0;
+    inverter.set_battery_limits(&limits).unwrap();
+    assert_eq!(inverter.read_battery_limits().unwrap(), limits);
+
+    limits.max_charge_power = 1000.0;
+    limits.charge_cutoff_soc = 80.0;
+    assert!(matches!(inverter.set_battery_limits(&limits), Err(Error::InvalidValue(_))));
+    assert_eq!(inverter.read_battery_limits().unwrap().max_charge_power, 2500.0);
+}
+
+#[test]
+fn forces_battery_charge_and_discharge() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    inverter.force_battery(ForcibleCommand::Charge { power: 3000.0, until: ForcibleTarget::Soc(90.0) }).unwrap();
+    assert_eq!(sim.words(registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE.addr(), 2), vec![1, 900]);
+    assert_eq!(inverter.read_numeric_register(&registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_SETTING_MODE).unwrap(), 1.0);
+    assert_eq!(inverter.read_numeric_register(&registers::STORAGE_FORCIBLE_CHARGE_POWER).unwrap(), 3000.0);
+
+    inverter.force_battery(ForcibleCommand::Discharge { power: 2000.0, until: ForcibleTarget::Minutes(45) }).unwrap();
+    assert_eq!(sim.words(registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE.addr(), 1), vec![2]);
+    assert_eq!(inverter.read_numeric_register(&registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_SETTING_MODE).unwrap(), 0.0);
+    assert_eq!(inverter.read_numeric_register(&registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_PERIOD).unwrap(), 45.0);
+    assert_eq!(inverter.read_numeric_register(&registers::STORAGE_FORCIBLE_DISCHARGE_POWER).unwrap(), 2000.0);
+
+    let too_low = ForcibleCommand::Charge { power: 3000.0, until: ForcibleTarget::Soc(5.0) };
+    assert!(matches!(inverter.force_battery(too_low), Err(Error::InvalidValue(_))));
+    inverter.force_battery(ForcibleCommand::Stop).unwrap();
+    assert_eq!(sim.words(registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE.addr(), 1), vec![0]);
+}
+
+#[test]
+fn sends_forcible_command_once() {
+    let mut simulator = Simulator::new();
+    simulator.drop_connections_after(1);
+    let sim = start(simulator);
+    let options = ConnectionOptions::new().retries(1).retry_backoff(Duration::ZERO);
+    let mut inverter = connect_with_options(&sim, 0, options);
+    let command = ForcibleCommand::Charge { power: 3000.0, until: ForcibleTarget::Minutes(30) };
+    assert!(inverter.force_battery(command).is_err());
+    // The settings are retried on a new connection, the command is not.
+    assert_eq!(sim.words(registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE_PERIOD.addr(), 1), vec![30]);
+    assert_eq!(sim.words(registers::STORAGE_FORCIBLE_CHARGE_DISCHARGE.addr(), 1), vec![0]);
+}
+
+#[test]
+fn powers_off_and_on() {
+    let mut simulator = Simulator::new();
+    simulator
+        .on_write(registers::STARTUP.addr(), registers::DEVICE_STATUS.addr(), &[0x0200])
+        .on_write(registers::SHUTDOWN.addr(), registers::DEVICE_STATUS.addr(), &[0x0301]);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let status = inverter.power_off(Duration::from_secs(5)).unwrap();
+    assert_eq!(status, DeviceStatus::Shutdown(0x0301));
+    assert_eq!(status.to_string(), "Shutdown, command");
+    assert_eq!(inverter.power_on(Duration::from_secs(5)).unwrap(), DeviceStatus::OnGrid(0x0200));
+}
+
+#[test]
+fn powers_on_into_standby_without_sunlight() {
+    let mut simulator = Simulator::new();
+    simulator.set_words(registers::DEVICE_STATUS.addr(), &[0x0301]);
+    simulator.on_write(registers::STARTUP.addr(), registers::DEVICE_STATUS.addr(), &[0xA000]);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    assert_eq!(inverter.power_on(Duration::from_secs(5)).unwrap(), DeviceStatus::Standby(0xA000));
+}
+
+#[test]
+fn does_not_take_unchanged_standby_as_started() {
+    let mut simulator = Simulator::new();
+    simulator.set_words(registers::DEVICE_STATUS.addr(), &[0xA000]);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    match inverter.power_on(Duration::from_millis(300)) {
+        Err(Error::StatusTimeout(DeviceStatus::Standby(0xA000))) => {},
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+#[test]
+fn fails_power_on_into_fault() {
+    let mut simulator = Simulator::new();
+    simulator.set_words(registers::DEVICE_STATUS.addr(), &[0x0301]);
+    simulator.on_write(registers::STARTUP.addr(), registers::DEVICE_STATUS.addr(), &[0x0300]);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    match inverter.power_on(Duration::from_secs(5)) {
+        Err(Error::DeviceFault(status)) => assert!(status.is_fault()),
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+#[test]
+fn does_not_retry_commands() {
+    let mut simulator = Simulator::new();
+    simulator.drop_connections_after(1);
+    let sim = start(simulator);
+    let options = ConnectionOptions::new().retries(3).retry_backoff(Duration::ZERO);
+    let mut inverter = connect_with_options(&sim, 0, options);
+    inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap();
+    assert!(inverter.power_off(Duration::from_secs(5)).is_err());
+    assert_eq!(inverter.stats().retries, 0);
+}
+
+#[test]
+fn times_out_waiting_for_status() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect(&sim, 0);
+    let started = Instant::now();
+    match inverter.power_off(Duration::from_millis(300)) {
+        Err(Error::StatusTimeout(DeviceStatus::OnGrid(_))) => {},
+        other => panic!("unexpected result: {:?}", other)
+    }
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn serves_configured_values() {
+    let mut simulator = Simulator::new();
+    simulator.set_value(&registers::ACTIVE_POWER, &RegisterValue::Numeric(1234.0)).unwrap();
+    simulator.set_value(&registers::SERIAL_NUMBER, &RegisterValue::String("ABC123".to_string())).unwrap();
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 1);
+    assert_eq!(inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap(), 1234.0);
+    assert_eq!(inverter.read_string_register(&registers::SERIAL_NUMBER).unwrap(), "ABC123");
+
+    sim.set_value(&registers::ACTIVE_POWER, &RegisterValue::Numeric(42.0)).unwrap();
+    assert_eq!(inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap(), 42.0);
+}
+
+#[test]
+fn rejects_unknown_slave_ids() {
+    let mut simulator = Simulator::new();
+    simulator.slave_ids(&[1]);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    match inverter.read_numeric_register(&registers::ACTIVE_POWER) {
+        Err(Error::Exception(ExceptionCode::GatewayTargetFailedToRespond)) => {},
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+#[test]
+fn read_many_falls_back_on_injected_exception() {
+    let mut simulator = Simulator::new();
+    simulator.inject_exception(32085, ExceptionCode::IllegalDataAddress);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let regs: [&dyn Register; 3] = [&registers::POWER_FACTOR, &registers::GRID_FREQUENCY, &registers::EFFICIENCY];
+    let mut values = inverter.read_many(&regs).into_iter();
+    assert_eq!(values.next().unwrap().unwrap(), RegisterValue::Numeric(0.999));
+    match values.next().unwrap().unwrap_err() {
+        Error::Exception(ExceptionCode::IllegalDataAddress) => {},
+        e => panic!("unexpected error: {}", e)
+    }
+    assert_eq!(values.next().unwrap().unwrap(), RegisterValue::Numeric(98.2));
+}
+
+#[test]
+fn applies_latency() {
+    let mut simulator = Simulator::new();
+    simulator.latency(Duration::from_millis(200));
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let started = Instant::now();
+    inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(200));
+}
+
+#[test]
+fn drops_connections() {
+    let mut simulator = Simulator::new();
+    simulator.drop_connections_after(1);
+    let sim = start(simulator);
+    let mut inverter = connect_with_options(&sim, 0, ConnectionOptions::new().retries(0));
+    inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap();
+    match inverter.read_numeric_register(&registers::ACTIVE_POWER) {
+        Err(Error::Connection(_)) => {},
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+#[test]
+fn reconnects_after_dropped_connection() {
+    let mut simulator = Simulator::new();
+    simulator.drop_connections_after(2);
+    let sim = start(simulator);
+    let mut inverter = connect(&sim, 0);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let log = events.clone();
+    inverter.on_connection_event(move |event| log.lock().unwrap().push(format!("{:?}", event)));
+
+    inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap();
+    inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap();
+    // The third read hits the dropped connection and is retried on a new one.
+    assert_eq!(inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap(), 4980.0);
+    assert_eq!(inverter.stats().reconnects, 1);
+    assert_eq!(inverter.stats().retries, 1);
+
+    let events = events.lock().unwrap();
+    assert!(events[0].starts_with("Lost"));
+    assert!(events[1].starts_with("Retry"));
+    assert_eq!(events[2], "Reconnected");
+}
+
+#[test]
+fn retries_reads_across_reconnects() {
+    let mut simulator = Simulator::new();
+    simulator.drop_connections_after(1);
+    let sim = start(simulator);
+    let options = ConnectionOptions::new().retries(1).retry_backoff(Duration::ZERO);
+    let mut inverter = connect_with_options(&sim, 0, options);
+    for _ in 0..3 {
+        assert_eq!(inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap(), 4980.0);
+    }
+    let stats = inverter.stats();
+    assert_eq!(stats.requests, 5);
+    assert_eq!(stats.retries, 2);
+    assert_eq!(stats.reconnects, 2);
+}
+
+#[test]
+fn retries_writes_across_reconnects() {
+    let mut simulator = Simulator::new();
+    simulator.drop_connections_after(1);
+    let sim = start(simulator);
+    let options = ConnectionOptions::new().retries(1).retry_backoff(Duration::ZERO);
+    let mut inverter = connect_with_options(&sim, 0, options);
+    inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap();
+    inverter.write_register(&registers::ACTIVE_POWER_PERCENTAGE_DERATING, 60.0).unwrap();
+    assert_eq!(sim.words(registers::ACTIVE_POWER_PERCENTAGE_DERATING.addr(), 1), vec![600]);
+    let stats = inverter.stats();
+    assert_eq!(stats.requests, 5);
+    assert_eq!(stats.retries, 2);
+    assert_eq!(stats.reconnects, 2);
+}
+
+#[test]
+fn does_not_reconnect_when_disabled() {
+    let mut simulator = Simulator::new();
+    simulator.drop_connections_after(1);
+    let sim = start(simulator);
+    let options = ConnectionOptions::new().retries(1).retry_backoff(Duration::ZERO).auto_reconnect(false);
+    let mut inverter = connect_with_options(&sim, 0, options);
+    inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap();
+    assert!(inverter.read_numeric_register(&registers::ACTIVE_POWER).is_err());
+    assert_eq!(inverter.stats().reconnects, 0);
+}
+
+#[test]
+fn times_out_slow_responses() {
+    let mut simulator = Simulator::new();
+    simulator.latency(Duration::from_millis(500));
+    let sim = start(simulator);
+    let mut inverter = connect_with_options(&sim, 0, ConnectionOptions::new().read_timeout(Duration::from_millis(100)));
+    match inverter.read_numeric_register(&registers::ACTIVE_POWER) {
+        Err(Error::Timeout) => {},
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+#[test]
+fn paces_requests() {
+    let sim = start(Simulator::new());
+    let mut inverter = connect_with_options(&sim, 0, ConnectionOptions::new().request_delay(Duration::from_millis(150)));
+    let started = Instant::now();
+    inverter.read_numeric_register(&registers::ACTIVE_POWER).unwrap();
+    inverter.read_numeric_register(&registers::EFFICIENCY).unwrap();
+    inverter.read_numeric_register(&registers::GRID_FREQUENCY).unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
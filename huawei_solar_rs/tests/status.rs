@@ -0,0 +1,39 @@
+use huawei_solar_rs::DeviceStatus;
+
+#[test]
+fn categorizes_known_codes() {
+    assert_eq!(DeviceStatus::from_code(0x0000), DeviceStatus::Standby(0x0000));
+    assert_eq!(DeviceStatus::from_code(0xA000), DeviceStatus::Standby(0xA000));
+    assert_eq!(DeviceStatus::from_code(0x0100), DeviceStatus::Starting(0x0100));
+    assert_eq!(DeviceStatus::from_code(0x0202), DeviceStatus::OnGrid(0x0202));
+    assert_eq!(DeviceStatus::from_code(0x0307), DeviceStatus::Shutdown(0x0307));
+    assert_eq!(DeviceStatus::from_code(0x0403), DeviceStatus::GridScheduling(0x0403));
+    assert_eq!(DeviceStatus::from_code(0x0A00), DeviceStatus::OffGrid(0x0A00));
+    assert_eq!(DeviceStatus::from_code(0x0201).to_string(), "Grid Connection, power limited");
+}
+
+#[test]
+fn keeps_unknown_codes() {
+    let status = DeviceStatus::from_code(0x1234);
+    assert_eq!(status, DeviceStatus::Unknown(0x1234));
+    assert_eq!(status.code(), 0x1234);
+    assert_eq!(status.description(), None);
+    assert_eq!(status.to_string(), "Unknown status 0x1234");
+
+    let status = DeviceStatus::from_code(0x0399);
+    assert_eq!(status, DeviceStatus::Shutdown(0x0399));
+    assert_eq!(status.description(), None);
+}
+
+#[test]
+fn predicates() {
+    assert!(DeviceStatus::from_code(0x0200).is_producing());
+    assert!(DeviceStatus::from_code(0x0401).is_producing());
+    assert!(DeviceStatus::from_code(0x0A00).is_producing());
+    assert!(!DeviceStatus::from_code(0xA000).is_producing());
+    assert!(!DeviceStatus::from_code(0x0300).is_producing());
+
+    assert!(DeviceStatus::from_code(0x0300).is_fault());
+    assert!(!DeviceStatus::from_code(0x0301).is_fault());
+    assert!(!DeviceStatus::from_code(0x0200).is_fault());
+}